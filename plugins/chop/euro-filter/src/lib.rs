@@ -48,9 +48,10 @@ impl Chop for EuroFilterChop {
 
     fn execute(&mut self, output: &mut ChopOutput, inputs: &OperatorInputs<ChopInput>) {
         if let Some(input) = &inputs.input(0) {
+            let rate = input.sample_rate();
             for filter in &mut self.filters {
                 filter.change_input(
-                    input.num_samples() as f64,
+                    rate,
                     self.params.min_cutoff,
                     self.params.beta,
                     self.params.d_cutoff,
@@ -59,7 +60,7 @@ impl Chop for EuroFilterChop {
 
             for _ in self.filters.len()..input.num_channels() {
                 self.filters.push(OneEuroImpl::new(
-                    input.num_samples() as f64,
+                    rate,
                     self.params.min_cutoff,
                     self.params.beta,
                     self.params.d_cutoff,
@@ -18,7 +18,7 @@ struct GeneratorChopParams {
     num_channels: u32,
     #[param(label = "Apply Scale", page = "Generator")]
     apply_scale: bool,
-    #[param(label = "Scale", page = "Generator")]
+    #[param(label = "Scale", page = "Generator", enable_if = "apply_scale")]
     scale: f32,
     #[param(label = "Operation", page = "Generator")]
     operation: Operation,
@@ -59,9 +59,6 @@ impl Op for GeneratorChop {
 impl Chop for GeneratorChop {
     #[tracing::instrument]
     fn execute(&mut self, output: &mut ChopOutput, inputs: &OperatorInputs<ChopInput>) {
-        let params = inputs.params();
-        params.enable_param("Scale", self.params.apply_scale);
-
         tracing::info!("Executing chop with params: {:?}", self.params);
         for i in 0..output.num_channels() {
             for j in 0..output.num_samples() {
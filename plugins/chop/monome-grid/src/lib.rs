@@ -10,19 +10,19 @@ enum Operation {
     Power,
 }
 
-#[derive(Params, Default, Clone, Debug, Eq, PartialEq)]
+#[derive(Params, Default, Clone, Debug)]
 struct MonomeGridParams {
     #[param(label = "Prefix", page = "Grid", default = "/touchdesigner")]
     prefix: String,
     #[param(label = "Hold", page = "Grid")]
     hold: bool,
+    changes: ParamChanges,
 }
 
 /// Struct representing our CHOP's state
 #[derive(Debug)]
 pub struct MonomeGrid {
     params: MonomeGridParams,
-    prev_params: MonomeGridParams,
     device: Option<Monome>,
     grid: [bool; 128],
 }
@@ -34,8 +34,8 @@ impl OpNew for MonomeGrid {
             params: MonomeGridParams {
                 prefix: "/touchdesigner".to_string(),
                 hold: false,
+                changes: Default::default(),
             },
-            prev_params: Default::default(),
             device: None,
             grid: [false; 128],
         }
@@ -55,8 +55,7 @@ impl Op for MonomeGrid {
 
 impl Chop for MonomeGrid {
     fn execute(&mut self, output: &mut ChopOutput, inputs: &OperatorInputs<ChopInput>) {
-        if self.params != self.prev_params || self.device.is_none() {
-            self.prev_params = self.params.clone();
+        if self.params.changed().prefix || self.device.is_none() {
             let device = match Monome::new(&self.params.prefix) {
                 Ok(device) => device,
                 Err(err) => {
@@ -13,14 +13,15 @@ enum PythonChopShape {
 }
 
 #[derive(Params, Default, Debug)]
+#[params(op = PythonChop)]
 struct PythonChopParams {
-    #[param(label="Speed", min=-10.0, max=10.0, default=1.0)]
+    #[param(label="Speed", min=-10.0, max=10.0, default=1.0, enable_if = "!input(0)")]
     speed: f32,
     #[param(label="Scale", min=-10.0, max=10.0, default=1.0)]
     scale: f32,
-    #[param(label = "Shape")]
+    #[param(label = "Shape", enable_if = "!input(0)")]
     shape: PythonChopShape,
-    #[param(label = "Reset")]
+    #[param(label = "Reset", enable_if = "!input(0)", on_pulse = "reset_filter")]
     reset: Pulse,
 }
 
@@ -96,10 +97,8 @@ impl Op for PythonChop {
         Some(Box::new(self))
     }
 
-    fn pulse_pressed(&mut self, name: &str) {
-        if name == "Reset" {
-            self.reset_filter();
-        }
+    fn dispatch_pulse(&mut self, name: &str) {
+        self.dispatch_on_pulse(name);
     }
 }
 
@@ -111,9 +110,6 @@ impl Chop for PythonChop {
     fn execute(&mut self, output: &mut ChopOutput, inputs: &OperatorInputs<ChopInput>) {
         self.execute_count += 1;
         if inputs.num_inputs() > 0 {
-            inputs.params().enable_param("Speed", false);
-            inputs.params().enable_param("Reset", false);
-            inputs.params().enable_param("Shape", false);
             if let Some(input) = inputs.input(0) {
                 let num_samples = output.num_samples();
                 let num_channels = output.num_channels();
@@ -126,9 +122,6 @@ impl Chop for PythonChop {
                 }
             }
         } else {
-            inputs.params().enable_param("Speed", true);
-            inputs.params().enable_param("Reset", true);
-            inputs.params().enable_param("Shape", true);
             // Apply Python class modifications
             self.params.speed *= self.speed;
 
@@ -8,9 +8,9 @@ use td_rs_derive::Params;
 struct WasmChopParams {
     #[param(label = "Apply Scale", page = "Filter")]
     apply_scale: bool,
-    #[param(label = "Scale", page = "Filter", min = - 10.0, max = 10.0)]
+    #[param(label = "Scale", page = "Filter", min = - 10.0, max = 10.0, enable_if = "apply_scale")]
     scale: f32,
-    #[param(label = "Wasm", page = "Wasm")]
+    #[param(label = "Wasm", page = "Wasm", enable_if = "input(0)")]
     wasm: FileParam,
 }
 
@@ -66,17 +66,19 @@ impl InfoDat for WasmChop {
 
 impl Chop for WasmChop {
     fn execute(&mut self, output: &mut ChopOutput, inputs: &OperatorInputs<ChopInput>) {
-        let params = inputs.params();
-        params.enable_param("Wasm", false);
-        params.enable_param("Scale", self.params.apply_scale);
+        let _ = self.try_execute(output, inputs);
+    }
 
+    fn try_execute(
+        &mut self,
+        output: &mut ChopOutput,
+        inputs: &OperatorInputs<ChopInput>,
+    ) -> Result<(), OpError> {
         if let Some(input) = &inputs.input(0) {
-            params.enable_param("Wasm", true);
-
             let wasm_file = &self.params.wasm;
             if wasm_file.exists() && wasm_file.is_file() {
                 let module = Module::from_file(&self.engine.clone(), wasm_file.as_path())
-                    .expect("Failed to load wasm file");
+                    .map_err(|e| OpError::error(format!("Failed to load wasm file: {}", e)))?;
                 self.module = Some(module);
             }
 
@@ -84,30 +86,38 @@ impl Chop for WasmChop {
                 let mut linker = Linker::new(&self.engine.clone());
 
                 let scale = self.params.scale;
-                linker.func_wrap("env", "scale", move || scale).unwrap();
+                linker
+                    .func_wrap("env", "scale", move || scale)
+                    .map_err(|e| OpError::error(format!("Failed to link module: {}", e)))?;
 
                 let mut store = Store::new(&self.engine.clone(), ());
-                let instance = match linker.instantiate(&mut store, module) {
-                    Ok(instance) => instance,
-                    Err(e) => {
-                        self.set_error(&format!("Failed to instantiate module: {}", e));
-                        return;
-                    }
-                };
+                let instance = linker
+                    .instantiate(&mut store, module)
+                    .map_err(|e| OpError::error(format!("Failed to instantiate module: {}", e)))?;
                 let execute = instance
                     .get_typed_func::<(u32, u32, f32), f32>(&mut store, "execute")
-                    .expect("Failed to get execute function");
+                    .map_err(|e| {
+                        OpError::error(format!("Failed to get execute function: {}", e))
+                    })?;
 
                 for i in 0..output.num_channels() {
                     for j in 0..output.num_samples() {
                         let res = execute
                             .call(&mut store, (i as u32, j as u32, input[i][j]))
-                            .expect("Failed to call execute function");
+                            .map_err(|e| {
+                                OpError::error(format!("Failed to call execute function: {}", e))
+                            })?;
                         output[i][j] = res;
                     }
                 }
             }
         }
+
+        Ok(())
+    }
+
+    fn error_output(&self) -> ErrorOutput {
+        ErrorOutput::Empty
     }
 
     fn general_info(&self, _inputs: &OperatorInputs<ChopInput>) -> ChopGeneralInfo {
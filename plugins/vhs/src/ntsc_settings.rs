@@ -1,5 +1,4 @@
 use td_rs_derive::{Param, Params};
-use td_rs_top::{MenuParam, Param};
 
 use ntscrs::ntsc::{
     ChromaDemodulationFilter, ChromaLowpass, ChromaNoiseSettings, FilterType,
@@ -7,7 +6,6 @@ use ntscrs::ntsc::{
     TrackingNoiseSettings, UseField, VHSEdgeWaveSettings, VHSSettings, VHSSharpenSettings,
     VHSTapeSpeed,
 };
-use td_rs_top::{OperatorParams, ParamInputs, ParamOptions, ParameterManager, StringParameter};
 
 #[derive(Param, Default, Clone, Debug)]
 enum NtscUseField {
@@ -484,7 +482,7 @@ impl From<NtscAllParams> for NtscEffect {
 
 #[derive(Params, Clone, Debug)]
 pub(crate) struct NtscAllParams {
-    #[param(min_slider=i32::MIN, max_slider= i32::MIN)]
+    #[param(min_slider = i32::MIN, max_slider = i32::MAX)]
     random_seed: i32,
 
     #[param(min_slider = 0.125, max_slider = 8.0)]
@@ -31,4 +31,19 @@ void releaseDownloadResult(TD::OP_SmartRef<TD::OP_TOPDownloadResult> &result) {
     result.release();
 }
 
+// The SDK doesn't mark these sop input queries const, although they don't
+// modify the input, so expose them through a const reference here.
+bool sopInputIsInside(const TD::OP_SOPInput &input, const TD::Position &pos) {
+    return const_cast<TD::OP_SOPInput &>(input).isInside(pos);
+}
+
+bool sopInputSendRay(const TD::OP_SOPInput &input, const TD::Position &pos,
+                     const TD::Vector &dir, TD::Position &hitPosition,
+                     float &hitLength, TD::Vector &hitNormal, float &hitU,
+                     float &hitV, int &hitPrimitiveIndex) {
+    return const_cast<TD::OP_SOPInput &>(input).sendRay(
+        pos, dir, hitPosition, hitLength, hitNormal, hitU, hitV,
+        hitPrimitiveIndex);
+}
+
 #endif // TD_RS_RUSTBASE_H
@@ -1,6 +1,7 @@
 use crate::cxx::OP_CHOPInput;
 use crate::{GetInput, OperatorInputs};
 use ref_cast::RefCast;
+use std::ffi::CStr;
 use std::ops::Index;
 
 /// A chop input.
@@ -34,6 +35,50 @@ impl ChopInput {
             )
         }
     }
+
+    /// Get the name of a channel. Names that aren't valid UTF-8 are returned
+    /// as an empty string.
+    pub fn channel_name(&self, index: usize) -> &str {
+        if index >= self.num_channels() {
+            panic!("index out of bounds");
+        }
+
+        unsafe {
+            let name = *self.input.nameData.add(index);
+            if name.is_null() {
+                return "";
+            }
+            CStr::from_ptr(name).to_str().unwrap_or_default()
+        }
+    }
+
+    /// Get a channel by name.
+    pub fn channel_by_name(&self, name: &str) -> Option<&[f32]> {
+        self.channels()
+            .find(|(channel_name, _)| *channel_name == name)
+            .map(|(_, channel)| channel)
+    }
+
+    /// Iterate over the name and samples of each channel.
+    pub fn channels(&self) -> impl Iterator<Item = (&str, &[f32])> {
+        (0..self.num_channels()).map(|i| (self.channel_name(i), self.channel(i)))
+    }
+
+    /// Get the sample rate of this input.
+    pub fn sample_rate(&self) -> f64 {
+        self.input.sampleRate
+    }
+
+    /// Get the index of the first sample of this input.
+    pub fn start_index(&self) -> f64 {
+        self.input.startIndex
+    }
+
+    /// Get the number of times the input operator has cooked. Useful for
+    /// telling whether the input has changed.
+    pub fn total_cooks(&self) -> i64 {
+        self.input.totalCooks
+    }
 }
 
 impl<'execute> GetInput<'execute, ChopInput> for OperatorInputs<'execute, ChopInput> {
@@ -8,6 +8,7 @@ include_cpp! {
     generate!("TD::OP_String")
     generate_pod!("TD::OP_CustomOPInfo")
     generate_pod!("TD::OP_CHOPInput")
+    generate_pod!("TD::OP_ObjectInput")
     generate!("TD::OP_SOPInput")
     generate_pod!("TD::OP_NumericParameter")
     generate_pod!("TD::OP_StringParameter")
@@ -26,6 +27,7 @@ include_cpp! {
     generate_pod!("TD::SOP_PrimitiveInfo")
     generate_pod!("TD::OP_DATInput")
     generate_pod!("TD::OP_NodeInfo")
+    generate_pod!("TD::OP_TimeInfo")
     generate!("TD::OP_Context")
     generate!("TD::OP_TOPInput")
     generate_pod!("TD::OP_TOPInputDownloadOptions")
@@ -45,6 +47,8 @@ include_cpp! {
     generate!("getDownloadData")
     generate!("getDownloadTextureDesc")
     generate!("releaseDownloadResult")
+    generate!("sopInputIsInside")
+    generate!("sopInputSendRay")
 }
 
 #[cfg(feature = "python")]
@@ -2,14 +2,20 @@
 #![feature(min_specialization)]
 #![feature(lazy_cell)]
 
-use std::cell::OnceCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ffi;
 use std::fmt::Formatter;
 use std::ops::Index;
 use std::pin::Pin;
 use std::sync::LazyLock;
 
+use ref_cast::RefCast;
+
+pub use messages::*;
+pub use panic::PanicGuard;
 pub use param::*;
+pub use preset::*;
 #[cfg(feature = "python")]
 pub use py::*;
 
@@ -24,16 +30,16 @@ pub static RUNTIME: LazyLock<tokio_core::runtime::Runtime> = LazyLock::new(|| {
 pub mod chop;
 pub mod cxx;
 pub mod dat;
+pub mod messages;
+pub mod object;
+pub mod panic;
 pub mod param;
+pub mod preset;
 #[cfg(feature = "python")]
 pub mod py;
 pub mod sop;
 pub mod top;
 
-static mut INFO_STR: OnceCell<String> = OnceCell::new();
-static mut ERROR_STR: OnceCell<String> = OnceCell::new();
-static mut WARNING_STR: OnceCell<String> = OnceCell::new();
-
 /// Metadata describing the operator plugin.
 pub trait OpInfo {
     /// The type of the operator.
@@ -91,76 +97,94 @@ pub trait Op {
         None
     }
 
+    /// Replace this instance's info popup messages. An empty string clears them.
     fn set_info(&mut self, info: &str) {
-        // # Safety
-        // The plugin is held on a single thread, and setters
-        // are only ever called from the body of the plugin
-        // and not exposed to C++.
-        unsafe {
-            let i = INFO_STR.get_mut();
-            if let Some(i) = i {
-                i.replace_range(.., info);
-            }
-        }
+        messages::with_current(|messages| messages.set_info(info));
     }
 
-    fn info(&self) -> &str {
-        unsafe { INFO_STR.get_or_init(|| "".to_string()) }
+    /// Add a line to this instance's info popup.
+    fn push_info(&mut self, info: &str) {
+        messages::with_current(|messages| messages.push_info(info));
     }
 
+    /// Replace this instance's errors. An empty string clears them.
     fn set_error(&mut self, error: &str) {
-        // # Safety
-        // The plugin is held on a single thread, and setters
-        // are only ever called from the body of the plugin
-        // and not exposed to C++.
-        unsafe {
-            let e = ERROR_STR.get_mut();
-            if let Some(e) = e {
-                e.replace_range(.., error);
-            }
-        }
+        messages::with_current(|messages| messages.set_error(error));
     }
 
-    fn error(&self) -> &str {
-        unsafe { ERROR_STR.get_or_init(|| "".to_string()) }
+    /// Add an error to this instance.
+    fn push_error(&mut self, error: &str) {
+        messages::with_current(|messages| messages.push_error(error));
     }
 
+    /// Replace this instance's warnings. An empty string clears them.
     fn set_warning(&mut self, warning: &str) {
-        // # Safety
-        // The plugin is held on a single thread, and setters
-        // are only ever called from the body of the plugin
-        // and not exposed to C++.
-        unsafe {
-            let w = WARNING_STR.get_mut();
-            if let Some(w) = w {
-                w.replace_range(.., warning);
-            }
-        }
+        messages::with_current(|messages| messages.set_warning(warning));
     }
 
-    fn warning(&self) -> &str {
-        unsafe { WARNING_STR.get_or_init(|| "".to_string()) }
+    /// Add a warning to this instance.
+    fn push_warning(&mut self, warning: &str) {
+        messages::with_current(|messages| messages.push_warning(warning));
     }
 
+    /// Call the methods named by `#[param(on_pulse = "...")]` for the pulse
+    /// `name`. This is called before [`Op::pulse_pressed`], and should
+    /// forward to the `dispatch_on_pulse` method which `#[derive(Params)]`
+    /// generates for the operator named with `#[params(op = ...)]`.
+    fn dispatch_pulse(&mut self, _name: &str) {}
+
     fn pulse_pressed(&mut self, _name: &str) {}
 }
 
+/// Information about the node an operator instance belongs to. This is an
+/// owned copy, so it can be stored by the operator.
+#[derive(Clone)]
 pub struct NodeInfo {
-    info: &'static cxx::OP_NodeInfo,
+    op_path: ffi::CString,
+    op_id: u32,
+    plugin_path: ffi::CString,
+    context: *mut cxx::OP_Context,
 }
 
 impl NodeInfo {
-    pub fn new(info: &'static cxx::OP_NodeInfo) -> Self {
-        Self { info }
+    pub fn new(info: &cxx::OP_NodeInfo) -> Self {
+        Self {
+            op_path: copy_c_str(info.opPath),
+            op_id: info.opId,
+            plugin_path: copy_c_str(info.pluginPath),
+            context: info.context,
+        }
+    }
+
+    /// The full path to the node, e.g. `/project1/myop1`.
+    pub fn op_path(&self) -> Result<&str, std::str::Utf8Error> {
+        self.op_path.to_str()
+    }
+
+    /// A unique id for the node, which stays the same for its lifetime.
+    pub fn op_id(&self) -> u32 {
+        self.op_id
+    }
+
+    /// The path to the plugin binary this node was loaded from.
+    pub fn plugin_path(&self) -> Result<&str, std::str::Utf8Error> {
+        self.plugin_path.to_str()
     }
 
     pub fn context(&self) -> Context {
         Context {
-            context: self.info.context,
+            context: self.context,
         }
     }
 }
 
+fn copy_c_str(s: *const std::ffi::c_char) -> ffi::CString {
+    if s.is_null() {
+        return ffi::CString::default();
+    }
+    unsafe { ffi::CStr::from_ptr(s) }.to_owned()
+}
+
 pub struct Context {
     #[allow(dead_code)]
     context: *mut cxx::OP_Context,
@@ -205,7 +229,11 @@ impl Context {
 
 impl std::fmt::Debug for NodeInfo {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "NodeInfo")
+        f.debug_struct("NodeInfo")
+            .field("op_path", &self.op_path)
+            .field("op_id", &self.op_id)
+            .field("plugin_path", &self.plugin_path)
+            .finish()
     }
 }
 
@@ -254,68 +282,232 @@ where
     {
         GetInput::num_inputs(self)
     }
+
+    /// Get the CHOP at `path`, if it exists.
+    pub fn chop_at(&self, path: &str) -> Option<&'execute chop::ChopInput> {
+        let path = ffi::CString::new(path).ok()?;
+        let chop = unsafe { self.inputs.getCHOP(path.as_ptr()) };
+        if chop.is_null() {
+            None
+        } else {
+            Some(chop::ChopInput::ref_cast(unsafe { &*chop }))
+        }
+    }
+
+    /// Get the DAT at `path`, if it exists.
+    pub fn dat_at(&self, path: &str) -> Option<&'execute dat::DatInput> {
+        let path = ffi::CString::new(path).ok()?;
+        let dat = unsafe { self.inputs.getDAT(path.as_ptr()) };
+        if dat.is_null() {
+            None
+        } else {
+            Some(dat::DatInput::ref_cast(unsafe { &*dat }))
+        }
+    }
+
+    /// Get the SOP at `path`, if it exists.
+    pub fn sop_at(&self, path: &str) -> Option<&'execute sop::SopInput> {
+        let path = ffi::CString::new(path).ok()?;
+        let sop = unsafe { self.inputs.getSOP(path.as_ptr()) };
+        if sop.is_null() {
+            None
+        } else {
+            Some(sop::SopInput::ref_cast(unsafe { &*sop }))
+        }
+    }
+
+    /// Get the TOP at `path`, if it exists.
+    pub fn top_at(&self, path: &str) -> Option<&'execute top::TopInput> {
+        let path = ffi::CString::new(path).ok()?;
+        let top = unsafe { self.inputs.getTOP(path.as_ptr()) };
+        if top.is_null() {
+            None
+        } else {
+            Some(top::TopInput::ref_cast(unsafe { &*top }))
+        }
+    }
+
+    /// Get the object COMP at `path`, if it exists.
+    pub fn object_at(&self, path: &str) -> Option<&'execute object::ObjectInput> {
+        let path = ffi::CString::new(path).ok()?;
+        let object = unsafe { self.inputs.getObject(path.as_ptr()) };
+        if object.is_null() {
+            None
+        } else {
+            Some(object::ObjectInput::ref_cast(unsafe { &*object }))
+        }
+    }
+
+    /// Get the transform that takes `from` into the space of `to`. Both must
+    /// be object parameters of this operator.
+    pub fn relative_transform(
+        &self,
+        from: &ObjectParam,
+        to: &ObjectParam,
+    ) -> Option<object::Transform> {
+        let from = ffi::CString::new(from.name.as_str()).ok()?;
+        let to = ffi::CString::new(to.name.as_str()).ok()?;
+        let mut matrix = [[0.0; 4]; 4];
+        let found = unsafe {
+            self.inputs
+                .getRelativeTransform(from.as_ptr(), to.as_ptr(), matrix.as_mut_ptr())
+        };
+        found.then_some(object::Transform(matrix))
+    }
+
+    /// Get timing information for the current cook.
+    pub fn time_info(&self) -> TimeInfo {
+        let info = self.inputs.getTimeInfo();
+        if info.is_null() {
+            return TimeInfo::default();
+        }
+        unsafe { TimeInfo::from(&*info) }
+    }
+}
+
+/// Timing information for the current cook, such as the rate and the time
+/// elapsed since the last cook.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct TimeInfo {
+    /// Frames since the application started, in root rate units. Counts up
+    /// forever.
+    pub abs_frame: i64,
+    /// The timeline frame number for this cook.
+    pub frame: f64,
+    /// The rate this node is cooking at. This may differ from the root rate
+    /// if the node is inside a component with component time.
+    pub rate: f64,
+    /// The frame number of the root timeline.
+    pub root_frame: f64,
+    /// The rate the file is running at.
+    pub root_rate: f64,
+    /// Frames elapsed since the last cook, in `rate` units. This is more than
+    /// one if frames were dropped, and zero on the first cook.
+    pub delta_frames: f64,
+    /// Milliseconds elapsed since the last cook, computed from
+    /// `delta_frames` rather than a CPU timer.
+    pub delta_ms: f64,
+}
+
+impl From<&cxx::OP_TimeInfo> for TimeInfo {
+    fn from(info: &cxx::OP_TimeInfo) -> Self {
+        Self {
+            abs_frame: info.absFrame,
+            frame: info.frame,
+            rate: info.rate,
+            root_frame: info.rootFrame,
+            root_rate: info.rootRate,
+            delta_frames: info.deltaFrames,
+            delta_ms: info.deltaMS,
+        }
+    }
 }
 
 /// Parameter inputs to an operator.
+///
+/// Every value read through the getters is also hashed into a fingerprint
+/// kept for the instance, which the operator compares between cooks to tell
+/// whether any parameter changed, for example to clear a caught panic.
 pub struct ParamInputs<'execute> {
     inputs: &'execute crate::cxx::OP_Inputs,
+    fingerprint: Cell<u64>,
+    first_update: Cell<bool>,
 }
 
 impl<'execute> ParamInputs<'execute> {
     /// Create a new operator input. This is only called by the operator.
     pub fn new(inputs: &'execute crate::cxx::OP_Inputs) -> ParamInputs<'execute> {
-        Self { inputs }
+        Self {
+            inputs,
+            fingerprint: Cell::new(FNV_OFFSET),
+            first_update: Cell::new(true),
+        }
+    }
+
+    /// Whether the operator hasn't been updated from its parameters before,
+    /// so state kept in the parameters themselves, such as which ones are
+    /// enabled, must be set in full. Always true outside of the operator.
+    pub fn is_first_update(&self) -> bool {
+        self.first_update.get()
+    }
+
+    pub(crate) fn set_first_update(&self, first: bool) {
+        self.first_update.set(first);
+    }
+
+    /// A hash of every parameter value read so far, used to tell whether
+    /// parameters changed between cooks.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint.get()
+    }
+
+    /// Run `read` and return a hash of only the parameter values it reads.
+    /// They are still included in the fingerprint.
+    pub fn hash_reads(&self, read: impl FnOnce()) -> u64 {
+        let fingerprint = self.fingerprint.replace(FNV_OFFSET);
+        read();
+        let hash = self.fingerprint.replace(fingerprint);
+        self.record("", &hash.to_ne_bytes());
+        hash
+    }
+
+    fn record(&self, name: &str, value: &[u8]) {
+        let mut hash = self.fingerprint.get();
+        for byte in name.bytes().chain(value.iter().copied()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        self.fingerprint.set(hash);
     }
 
     /// Get a float parameter.
     pub fn get_float(&self, name: &str, index: usize) -> f64 {
-        unsafe {
-            self.inputs
-                .getParDouble(ffi::CString::new(name).unwrap().into_raw(), index as i32)
-        }
+        let value = unsafe { self.inputs.getParDouble(param_name(name), index as i32) };
+        self.record(name, &value.to_ne_bytes());
+        value
     }
 
     /// Get an integer parameter.
     pub fn get_int(&self, name: &str, index: usize) -> i32 {
-        unsafe {
-            self.inputs
-                .getParInt(ffi::CString::new(name).unwrap().into_raw(), index as i32)
-        }
+        let value = unsafe { self.inputs.getParInt(param_name(name), index as i32) };
+        self.record(name, &value.to_ne_bytes());
+        value
     }
 
     /// Get a string parameter.
     pub fn get_string(&self, name: &str) -> &str {
         unsafe {
-            let res = self
-                .inputs
-                .getParString(ffi::CString::new(name).unwrap().into_raw());
-            ffi::CStr::from_ptr(res).to_str().unwrap()
+            let res = self.inputs.getParString(param_name(name));
+            let res = ffi::CStr::from_ptr(res);
+            self.record(name, res.to_bytes());
+            res.to_str().unwrap()
         }
     }
 
     /// Get a toggle parameter.
     pub fn get_toggle(&self, name: &str) -> bool {
-        unsafe {
-            self.inputs
-                .getParInt(ffi::CString::new(name).unwrap().into_raw(), 0)
-                != 0
-        }
+        let value = unsafe { self.inputs.getParInt(param_name(name), 0) };
+        self.record(name, &value.to_ne_bytes());
+        value != 0
     }
 
     /// Enable or disable a parameter.
     pub fn enable_param(&self, name: &str, enable: bool) {
         unsafe {
-            self.inputs
-                .enablePar(ffi::CString::new(name).unwrap().into_raw(), enable);
+            self.inputs.enablePar(param_name(name), enable);
         }
     }
 
+    /// Whether the operator's input at `index` is connected.
+    pub fn input_connected(&self, index: usize) -> bool {
+        index < self.inputs.getNumInputs() as usize
+    }
+
     /// Get a chop parameter.
     fn get_chop(&self, name: &str) -> ChopParam {
         unsafe {
-            let chop = self
-                .inputs
-                .getParCHOP(ffi::CString::new(name).unwrap().into_raw());
+            let chop = self.inputs.getParCHOP(param_name(name));
+            self.record(name, &(chop as usize).to_ne_bytes());
             if chop.is_null() {
                 ChopParam { input: None }
             } else {
@@ -324,11 +516,22 @@ impl<'execute> ParamInputs<'execute> {
         }
     }
 
+    fn get_object(&self, name: &str) -> Option<*const cxx::OP_ObjectInput> {
+        unsafe {
+            let object = self.inputs.getParObject(param_name(name));
+            self.record(name, &(object as usize).to_ne_bytes());
+            if object.is_null() {
+                None
+            } else {
+                Some(object)
+            }
+        }
+    }
+
     fn get_sop(&self, name: &str) -> SopParam {
         unsafe {
-            let sop = self
-                .inputs
-                .getParSOP(ffi::CString::new(name).unwrap().into_raw());
+            let sop = self.inputs.getParSOP(param_name(name));
+            self.record(name, &(sop as usize).to_ne_bytes());
             if sop.is_null() {
                 SopParam { input: None }
             } else {
@@ -339,9 +542,8 @@ impl<'execute> ParamInputs<'execute> {
 
     fn get_top(&self, name: &str) -> TopParam {
         unsafe {
-            let top = self
-                .inputs
-                .getParTOP(ffi::CString::new(name).unwrap().into_raw());
+            let top = self.inputs.getParTOP(param_name(name));
+            self.record(name, &(top as usize).to_ne_bytes());
             if top.is_null() {
                 TopParam { input: None }
             } else {
@@ -352,9 +554,8 @@ impl<'execute> ParamInputs<'execute> {
 
     fn get_dat(&self, name: &str) -> DatParam {
         unsafe {
-            let dat = self
-                .inputs
-                .getParDAT(ffi::CString::new(name).unwrap().into_raw());
+            let dat = self.inputs.getParDAT(param_name(name));
+            self.record(name, &(dat as usize).to_ne_bytes());
             if dat.is_null() {
                 DatParam { input: None }
             } else {
@@ -366,9 +567,7 @@ impl<'execute> ParamInputs<'execute> {
     #[cfg(feature = "python")]
     fn get_python(&self, name: &str) -> *mut pyo3_ffi::PyObject {
         unsafe {
-            let python = self
-                .inputs
-                .getParPython(ffi::CString::new(name).unwrap().into_raw());
+            let python = self.inputs.getParPython(param_name(name));
             if python.is_null() {
                 std::ptr::null_mut()
             } else {
@@ -377,11 +576,11 @@ impl<'execute> ParamInputs<'execute> {
         }
     }
 
-    fn get_double_arr<const N: usize>(&self, name: &str) -> [f64; N] {
+    fn get_double_arr<const N: usize>(&self, name_str: &str) -> [f64; N] {
         assert!(N > 1 && N <= 4);
         unsafe {
             let mut arr = [0.0; N];
-            let name = ffi::CString::new(name).unwrap().into_raw();
+            let name = param_name(name_str);
             match N {
                 2 => {
                     let mut a = 0.0;
@@ -425,15 +624,18 @@ impl<'execute> ParamInputs<'execute> {
                 _ => {}
             };
 
+            for v in arr {
+                self.record(name_str, &v.to_ne_bytes());
+            }
             arr
         }
     }
 
-    fn get_int_arr<const N: usize>(&self, name: &str) -> [i32; N] {
+    fn get_int_arr<const N: usize>(&self, name_str: &str) -> [i32; N] {
         assert!(N > 1 && N <= 4);
         unsafe {
             let mut arr = [0; N];
-            let name = ffi::CString::new(name).unwrap().into_raw();
+            let name = param_name(name_str);
             match N {
                 2 => {
                     let mut a = 0;
@@ -476,11 +678,38 @@ impl<'execute> ParamInputs<'execute> {
                 }
                 _ => {}
             };
+            for v in arr {
+                self.record(name_str, &v.to_ne_bytes());
+            }
             arr
         }
     }
 }
 
+thread_local! {
+    static PARAM_NAMES: RefCell<HashMap<Box<str>, ffi::CString>> = RefCell::new(HashMap::new());
+}
+
+/// Get a null terminated parameter name to pass to TouchDesigner. Names are
+/// interned, so only the first lookup of each name allocates.
+fn param_name(name: &str) -> *const ffi::c_char {
+    PARAM_NAMES.with(|names| {
+        let mut names = names.borrow_mut();
+        if let Some(c_name) = names.get(name) {
+            return c_name.as_ptr();
+        }
+        let c_name = ffi::CString::new(name).expect("Parameter name contains a nul byte");
+        // The string's buffer doesn't move when the map grows, and names are
+        // never removed, so the pointer stays valid for the thread.
+        let ptr = c_name.as_ptr();
+        names.insert(name.into(), c_name);
+        ptr
+    })
+}
+
+const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
 /// Get an input to an operator.
 pub trait GetInput<'execute, Op>: Index<usize, Output = Self::Input> {
     /// The type of the input.
@@ -561,11 +790,7 @@ pub unsafe fn op_info<T: OpInfo + PyMethods + PyGetSets>(
 
 /// Base functionality for all operator types.
 pub fn op_init() {
-    unsafe {
-        INFO_STR.get_or_init(|| "".to_string());
-        ERROR_STR.get_or_init(|| "".to_string());
-        WARNING_STR.get_or_init(|| "".to_string());
-    }
+    panic::install_hook();
 
     #[cfg(feature = "tracing")]
     {
@@ -0,0 +1,296 @@
+use std::cell::Cell;
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+thread_local! {
+    static CURRENT: Cell<*mut OpMessages> = const { Cell::new(std::ptr::null_mut()) };
+}
+
+/// The info, warning and error messages reported by a single operator
+/// instance.
+///
+/// Each operator instance owns one of these. Messages pushed during a cook
+/// are stacked and reported to TouchDesigner joined by newlines, and are
+/// cleared automatically at the start of the next cook.
+#[derive(Debug, Default, Clone)]
+pub struct OpMessages {
+    info: Vec<String>,
+    warnings: Vec<String>,
+    errors: Vec<String>,
+}
+
+impl OpMessages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an info message.
+    pub fn push_info(&mut self, info: impl Into<String>) {
+        push(&mut self.info, info.into());
+    }
+
+    /// Add a warning message.
+    pub fn push_warning(&mut self, warning: impl Into<String>) {
+        push(&mut self.warnings, warning.into());
+    }
+
+    /// Add an error message.
+    pub fn push_error(&mut self, error: impl Into<String>) {
+        push(&mut self.errors, error.into());
+    }
+
+    /// Replace all info messages with a single message. An empty message
+    /// clears them.
+    pub fn set_info(&mut self, info: impl Into<String>) {
+        self.info.clear();
+        self.push_info(info);
+    }
+
+    /// Replace all warning messages with a single message. An empty message
+    /// clears them.
+    pub fn set_warning(&mut self, warning: impl Into<String>) {
+        self.warnings.clear();
+        self.push_warning(warning);
+    }
+
+    /// Replace all error messages with a single message. An empty message
+    /// clears them.
+    pub fn set_error(&mut self, error: impl Into<String>) {
+        self.errors.clear();
+        self.push_error(error);
+    }
+
+    /// Add an error returned from a cook as a warning or an error,
+    /// depending on its severity.
+    pub fn report(&mut self, error: OpError) {
+        match error.severity {
+            Severity::Warning => self.push_warning(error.message),
+            Severity::Error => self.push_error(error.message),
+        }
+    }
+
+    /// Remove all messages.
+    pub fn clear(&mut self) {
+        self.info.clear();
+        self.warnings.clear();
+        self.errors.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.info.is_empty() && self.warnings.is_empty() && self.errors.is_empty()
+    }
+
+    /// The stacked info messages, one per line.
+    pub fn info(&self) -> String {
+        self.info.join("\n")
+    }
+
+    /// The stacked warning messages, one per line.
+    pub fn warning(&self) -> String {
+        self.warnings.join("\n")
+    }
+
+    /// The stacked error messages, one per line.
+    pub fn error(&self) -> String {
+        self.errors.join("\n")
+    }
+
+    /// Make these the messages that [`crate::Op`]'s message setters write to
+    /// until the returned scope is dropped. This is only called by the
+    /// operator around each call into plugin code.
+    pub fn enter(&mut self) -> MessageScope<'_> {
+        let prev = CURRENT.with(|current| current.replace(self as *mut OpMessages));
+        MessageScope {
+            messages: self,
+            prev,
+            _marker: PhantomData,
+        }
+    }
+}
+
+fn push(messages: &mut Vec<String>, message: String) {
+    if !message.is_empty() {
+        messages.push(message);
+    }
+}
+
+/// Routes message setters to an instance's [`OpMessages`] while alive.
+pub struct MessageScope<'a> {
+    messages: *mut OpMessages,
+    prev: *mut OpMessages,
+    _marker: PhantomData<&'a mut OpMessages>,
+}
+
+impl Deref for MessageScope<'_> {
+    type Target = OpMessages;
+
+    fn deref(&self) -> &Self::Target {
+        // # Safety
+        // The scope holds the unique borrow of the messages for its lifetime.
+        unsafe { &*self.messages }
+    }
+}
+
+impl DerefMut for MessageScope<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // # Safety
+        // The scope holds the unique borrow of the messages for its lifetime.
+        unsafe { &mut *self.messages }
+    }
+}
+
+impl Drop for MessageScope<'_> {
+    fn drop(&mut self) {
+        CURRENT.with(|current| current.set(self.prev));
+    }
+}
+
+/// Run `f` on the messages of the operator instance currently calling into
+/// plugin code. Does nothing if called outside of an operator callback.
+pub(crate) fn with_current(f: impl FnOnce(&mut OpMessages)) {
+    let current = CURRENT.with(|current| current.get());
+    // # Safety
+    // The pointer is only set while a `MessageScope` holds the unique
+    // borrow of the messages, and plugins are called on a single thread.
+    if let Some(messages) = unsafe { current.as_mut() } {
+        f(messages);
+    }
+}
+
+/// Add a warning to the current instance unless it already has it, as
+/// parameters are read more than once per cook.
+pub(crate) fn warn_once(warning: String) {
+    with_current(|messages| {
+        if !messages.warnings.contains(&warning) {
+            messages.push_warning(warning);
+        }
+    });
+}
+
+/// How serious an [`OpError`] is.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum Severity {
+    /// Reported as a warning on the node; the output is still used.
+    Warning,
+    /// Reported as an error on the node.
+    #[default]
+    Error,
+}
+
+/// An error returned from a cook, which is reported on the node's
+/// error or warning string depending on its severity.
+///
+/// Any [`std::error::Error`] converts into an `OpError` with
+/// [`Severity::Error`], so `?` can be used in the fallible execute methods.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct OpError {
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl OpError {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+        }
+    }
+
+    /// Create an error which is reported as a node error.
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Create an error which is reported as a node warning.
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+}
+
+impl std::fmt::Display for OpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl<E: std::error::Error> From<E> for OpError {
+    fn from(error: E) -> Self {
+        Self::error(error.to_string())
+    }
+}
+
+/// What happens to an operator's output when a fallible execute returns an
+/// [`OpError`].
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum ErrorOutput {
+    /// Keep whatever was written to the output before the error.
+    #[default]
+    Unchanged,
+    /// Clear the output.
+    Empty,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_messages_stack() {
+        let mut messages = OpMessages::new();
+        messages.push_error("first");
+        messages.push_error("second");
+        messages.push_error("");
+        assert_eq!(messages.error(), "first\nsecond");
+    }
+
+    #[test]
+    fn test_set_replaces() {
+        let mut messages = OpMessages::new();
+        messages.push_warning("first");
+        messages.push_warning("second");
+        messages.set_warning("third");
+        assert_eq!(messages.warning(), "third");
+        messages.set_warning("");
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_scope_routes_current() {
+        let mut outer = OpMessages::new();
+        let mut inner = OpMessages::new();
+        {
+            let _outer = outer.enter();
+            {
+                let _inner = inner.enter();
+                with_current(|messages| messages.push_info("inner"));
+            }
+            with_current(|messages| messages.push_info("outer"));
+        }
+        with_current(|messages| messages.push_info("none"));
+        assert_eq!(outer.info(), "outer");
+        assert_eq!(inner.info(), "inner");
+    }
+
+    #[test]
+    fn test_report_severity() {
+        let mut messages = OpMessages::new();
+        messages.report(OpError::warning("warning"));
+        messages.report(OpError::error("error"));
+        assert_eq!(messages.warning(), "warning");
+        assert_eq!(messages.error(), "error");
+        messages.clear();
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_warn_once() {
+        let mut messages = OpMessages::new();
+        {
+            let _scope = messages.enter();
+            warn_once("warning".to_string());
+            warn_once("warning".to_string());
+        }
+        assert_eq!(messages.warning(), "warning");
+    }
+}
@@ -0,0 +1,111 @@
+use crate::cxx::OP_ObjectInput;
+use ref_cast::RefCast;
+use std::ffi::CStr;
+use std::ops::Index;
+
+/// A 4x4 transform matrix, indexed `[row][column]` as in TouchDesigner.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Transform(pub [[f64; 4]; 4]);
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform([
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]);
+
+    /// Get the translation part of the transform.
+    pub fn translation(&self) -> [f64; 3] {
+        [self.0[0][3], self.0[1][3], self.0[2][3]]
+    }
+}
+
+impl Index<usize> for Transform {
+    type Output = [f64; 4];
+
+    fn index(&self, row: usize) -> &Self::Output {
+        &self.0[row]
+    }
+}
+
+impl From<[[f64; 4]; 4]> for Transform {
+    fn from(value: [[f64; 4]; 4]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Transform> for [[f64; 4]; 4] {
+    fn from(value: Transform) -> Self {
+        value.0
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<Transform> for glam::DMat4 {
+    fn from(value: Transform) -> Self {
+        // glam is column-major
+        glam::DMat4::from_cols_array_2d(&value.0).transpose()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl From<glam::DMat4> for Transform {
+    fn from(value: glam::DMat4) -> Self {
+        Self(value.transpose().to_cols_array_2d())
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<Transform> for nalgebra::Matrix4<f64> {
+    fn from(value: Transform) -> Self {
+        nalgebra::Matrix4::from_fn(|row, col| value.0[row][col])
+    }
+}
+
+#[cfg(feature = "nalgebra")]
+impl From<nalgebra::Matrix4<f64>> for Transform {
+    fn from(value: nalgebra::Matrix4<f64>) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (row, m_row) in m.iter_mut().enumerate() {
+            for (col, v) in m_row.iter_mut().enumerate() {
+                *v = value[(row, col)];
+            }
+        }
+        Self(m)
+    }
+}
+
+/// An object COMP input.
+#[repr(transparent)]
+#[derive(RefCast)]
+pub struct ObjectInput {
+    input: OP_ObjectInput,
+}
+
+impl ObjectInput {
+    /// Get the path of the object.
+    pub fn op_path(&self) -> Result<&str, std::str::Utf8Error> {
+        unsafe { CStr::from_ptr(self.input.opPath) }.to_str()
+    }
+
+    /// Get the id of the object.
+    pub fn op_id(&self) -> u32 {
+        self.input.opId
+    }
+
+    /// Get the transform of the object relative to world space.
+    pub fn world_transform(&self) -> Transform {
+        Transform(self.input.worldTransform)
+    }
+
+    /// Get the transform of the object relative to its parent.
+    pub fn local_transform(&self) -> Transform {
+        Transform(self.input.localTransform)
+    }
+
+    /// Get the number of times the object has cooked.
+    pub fn total_cooks(&self) -> i64 {
+        self.input.totalCooks
+    }
+}
@@ -0,0 +1,215 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Once;
+
+use crate::{OpMessages, OperatorParams, ParamInputs};
+
+thread_local! {
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// Install a panic hook which records the location of a panic so it can
+/// be shown on the node. The previous hook is still called.
+pub(crate) fn install_hook() {
+    HOOK.call_once(|| {
+        let prev = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            LOCATION.with(|loc| *loc.borrow_mut() = location);
+            prev(info);
+        }));
+    });
+}
+
+/// Keeps panics in plugin code from unwinding into TouchDesigner.
+///
+/// A caught panic is reported as an error on the node and poisons the
+/// instance. While poisoned, the operator skips its cooks until a parameter
+/// changes, a pulse is pressed, or the node is recreated. If the plugin's
+/// constructor panicked, the instance stays poisoned until it is recreated.
+#[derive(Debug, Default)]
+pub struct PanicGuard {
+    poisoned: bool,
+    fatal: bool,
+    params: Option<u64>,
+}
+
+impl PanicGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
+    /// Keep the instance poisoned until the node is recreated. This is
+    /// used when the plugin's constructor panicked, leaving nothing to run.
+    pub fn set_fatal(&mut self) {
+        self.poisoned = true;
+        self.fatal = true;
+    }
+
+    /// Clear the poisoned state and `messages` so the next cook runs again.
+    /// Has no effect once [`PanicGuard::set_fatal`] has been called.
+    pub fn reset(&mut self, messages: &mut OpMessages) {
+        if !self.fatal {
+            self.poisoned = false;
+            messages.clear();
+        }
+    }
+
+    /// Call `f` with `messages` receiving any messages set by the plugin,
+    /// returning `None` if it panicked. This is only called by the operator
+    /// around each call into plugin code.
+    pub fn call<R>(&mut self, messages: &mut OpMessages, f: impl FnOnce() -> R) -> Option<R> {
+        let res = {
+            let _messages = messages.enter();
+            catch_unwind(AssertUnwindSafe(f))
+        };
+        match res {
+            Ok(res) => Some(res),
+            Err(payload) => {
+                // Keep a single panic message while poisoned rather than
+                // stacking one per callback.
+                if !self.poisoned {
+                    messages.push_error(panic_message(payload.as_ref()));
+                }
+                self.poisoned = true;
+                None
+            }
+        }
+    }
+
+    /// Update the operator's parameters from `inputs`, catching any panic.
+    /// If the instance is poisoned and the values differ from those read
+    /// before the panic, the instance is reset and its messages cleared.
+    pub fn update_params<'a>(
+        &mut self,
+        messages: &mut OpMessages,
+        params: impl FnOnce() -> Option<Box<&'a mut dyn OperatorParams>>,
+        inputs: &ParamInputs,
+    ) {
+        inputs.set_first_update(self.params.is_none());
+        let updated = self.call(messages, || {
+            if let Some(params) = params() {
+                params.update(inputs);
+            }
+        });
+        if updated.is_some() {
+            self.params_updated(messages, inputs.fingerprint());
+        }
+    }
+
+    /// Tell the operator's parameters that the cook has finished, catching
+    /// any panic.
+    pub fn end_cook<'a>(
+        &mut self,
+        messages: &mut OpMessages,
+        params: impl FnOnce() -> Option<Box<&'a mut dyn OperatorParams>>,
+    ) {
+        self.call(messages, || {
+            if let Some(params) = params() {
+                params.end_cook();
+            }
+        });
+    }
+
+    fn params_updated(&mut self, messages: &mut OpMessages, fingerprint: u64) {
+        if self.poisoned && self.params != Some(fingerprint) {
+            self.reset(messages);
+        }
+        self.params = Some(fingerprint);
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = if let Some(s) = payload.downcast_ref::<&str>() {
+        s
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.as_str()
+    } else {
+        "Box<dyn Any>"
+    };
+    match LOCATION.with(|loc| loc.borrow_mut().take()) {
+        Some(location) => format!("Panicked at {}: {}", location, message),
+        None => format!("Panicked: {}", message),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn panicking_call(guard: &mut PanicGuard, messages: &mut OpMessages) -> Option<()> {
+        guard.call(messages, || panic!("boom"))
+    }
+
+    #[test]
+    fn test_call_returns_value() {
+        let mut guard = PanicGuard::new();
+        let mut messages = OpMessages::new();
+        assert_eq!(guard.call(&mut messages, || 1), Some(1));
+        assert!(!guard.is_poisoned());
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_panic_poisons() {
+        let mut guard = PanicGuard::new();
+        let mut messages = OpMessages::new();
+        assert_eq!(panicking_call(&mut guard, &mut messages), None);
+        assert!(guard.is_poisoned());
+        assert!(messages.error().contains("boom"));
+    }
+
+    #[test]
+    fn test_panic_reported_once() {
+        let mut guard = PanicGuard::new();
+        let mut messages = OpMessages::new();
+        panicking_call(&mut guard, &mut messages);
+        panicking_call(&mut guard, &mut messages);
+        assert_eq!(messages.error().lines().count(), 1);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut guard = PanicGuard::new();
+        let mut messages = OpMessages::new();
+        panicking_call(&mut guard, &mut messages);
+        guard.reset(&mut messages);
+        assert!(!guard.is_poisoned());
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn test_fatal_ignores_reset() {
+        let mut guard = PanicGuard::new();
+        let mut messages = OpMessages::new();
+        panicking_call(&mut guard, &mut messages);
+        guard.set_fatal();
+        guard.reset(&mut messages);
+        guard.params_updated(&mut messages, 1);
+        guard.params_updated(&mut messages, 2);
+        assert!(guard.is_poisoned());
+        assert!(!messages.is_empty());
+    }
+
+    #[test]
+    fn test_params_changed_resets() {
+        let mut guard = PanicGuard::new();
+        let mut messages = OpMessages::new();
+        guard.params_updated(&mut messages, 1);
+        panicking_call(&mut guard, &mut messages);
+        guard.params_updated(&mut messages, 1);
+        assert!(guard.is_poisoned());
+        guard.params_updated(&mut messages, 2);
+        assert!(!guard.is_poisoned());
+        assert!(messages.is_empty());
+    }
+}
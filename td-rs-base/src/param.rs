@@ -1,15 +1,20 @@
 use crate::chop::ChopInput;
+use crate::object::{ObjectInput, Transform};
+use crate::preset::{Preset, PresetError, PresetValue};
 use crate::sop::{Color, SopInput};
 use crate::{cxx, ParamInputs};
 use ref_cast::RefCast;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::ffi;
 use std::ffi::{c_char, CString};
 use std::ops::{Deref, DerefMut};
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::Mutex;
 
 /// A numeric parameter.
-// TODO: switch to enum to describe parameter types
 #[derive(Debug)]
 pub struct NumericParameter {
     /// The name of the parameter.
@@ -58,6 +63,466 @@ pub trait OperatorParams {
     fn register(&mut self, parameter_manager: &mut ParameterManager);
     /// Update parameters from operator input.
     fn update(&mut self, inputs: &ParamInputs);
+    /// Describe the parameters without registering them. This is empty
+    /// unless generated by `#[derive(Params)]`.
+    fn describe() -> &'static [ParamSchema]
+    where
+        Self: Sized + 'static,
+    {
+        &[]
+    }
+    /// Register parameters as part of a flattened group.
+    fn register_group(&mut self, parameter_manager: &mut ParameterManager, _group: ParamGroup) {
+        self.register(parameter_manager);
+    }
+    /// Update parameters registered as part of a flattened group.
+    fn update_group(&mut self, inputs: &ParamInputs, _group: ParamGroup) {
+        self.update(inputs);
+    }
+    /// Called by the operator once a cook has finished. Parameters are
+    /// updated more than once per cook, so changes tracked by a
+    /// [`ParamChanges`] field are kept until then.
+    fn end_cook(&mut self) {}
+    /// Describe parameters registered as part of a flattened group.
+    fn describe_group(_group: ParamGroup) -> Vec<ParamSchema>
+    where
+        Self: Sized + 'static,
+    {
+        Self::describe().to_vec()
+    }
+    /// Add the values of parameters registered as part of a flattened group
+    /// to `preset`.
+    fn preset_group(&self, _preset: &mut Preset, _group: ParamGroup) {}
+    /// Apply the values in `preset` to parameters registered as part of a
+    /// flattened group. Values which are out of range or the wrong kind are
+    /// added to `errors` rather than applied.
+    fn apply_preset_group(
+        &mut self,
+        _preset: &Preset,
+        _group: ParamGroup,
+        _errors: &mut Vec<PresetError>,
+    ) {
+    }
+    /// Take a snapshot of the parameter values. This is empty unless
+    /// generated by `#[derive(Params)]`.
+    fn to_preset(&self) -> Preset {
+        let mut preset = Preset::new();
+        self.preset_group(&mut preset, ParamGroup::default());
+        preset
+    }
+    /// Apply the values in `preset`. Values for unknown parameters, out of
+    /// range or of the wrong kind are reported, and the rest are applied.
+    fn apply_preset(&mut self, preset: &Preset) -> Result<(), Vec<PresetError>> {
+        let mut errors = Vec::new();
+        self.apply_preset_group(preset, ParamGroup::default(), &mut errors);
+        // Checked after applying, as a count may add parameters.
+        let known = self.to_preset();
+        errors.extend(
+            preset
+                .iter()
+                .filter(|(name, _)| !known.contains(name))
+                .map(|(name, _)| PresetError::Unknown {
+                    name: name.to_string(),
+                }),
+        );
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Which fields of a `#[derive(Params)]` struct changed since the operator
+/// last finished a cook.
+///
+/// Adding a field of this type to a params struct makes the derive track
+/// changes, which are read with the generated `changed` method and can run
+/// `#[param(on_change = "method")]` hooks. Every field counts as changed in
+/// the first cook. Outside of an operator, changes are kept until
+/// [`OperatorParams::end_cook`] is called.
+#[derive(Debug, Default, Clone)]
+pub struct ParamChanges {
+    hashes: Vec<Option<u64>>,
+    changed: Vec<bool>,
+    updated: Vec<bool>,
+}
+
+impl ParamChanges {
+    /// Start an update of `len` fields.
+    pub fn begin(&mut self, len: usize) {
+        self.hashes.resize(len, None);
+        self.changed.resize(len, false);
+        self.updated.clear();
+        self.updated.resize(len, false);
+    }
+
+    /// Record the hash of the values read into the field at `index`,
+    /// returning whether it changed since it was last recorded.
+    pub fn record(&mut self, index: usize, hash: u64) -> bool {
+        let changed = self.hashes[index] != Some(hash);
+        self.hashes[index] = Some(hash);
+        self.changed[index] |= changed;
+        self.updated[index] = changed;
+        changed
+    }
+
+    /// Forget which fields changed, once the cook has finished.
+    pub fn end_cook(&mut self) {
+        self.changed.fill(false);
+    }
+
+    /// Whether the field at `index` changed since the last cook finished.
+    pub fn is_changed(&self, index: usize) -> bool {
+        self.changed.get(index).copied().unwrap_or(false)
+    }
+
+    /// Whether the field at `index` changed in the current update, so
+    /// `on_change` hooks run once per change rather than once per update.
+    pub fn is_updated(&self, index: usize) -> bool {
+        self.updated.get(index).copied().unwrap_or(false)
+    }
+
+    /// Whether any field changed.
+    pub fn any(&self) -> bool {
+        self.changed.iter().any(|changed| *changed)
+    }
+}
+
+/// Where a group of parameters flattened into another struct with
+/// `#[param(flatten)]`, or an element of a `#[param(count = ...)]` sequence,
+/// is registered.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ParamGroup {
+    /// A prefix added to the names of the parameters.
+    pub prefix: &'static str,
+    /// The numbers of the sequence elements the group is in, added to the
+    /// end of the names of the parameters.
+    pub suffix: &'static str,
+    /// A page which replaces the pages of the parameters.
+    pub page: Option<&'static str>,
+}
+
+thread_local! {
+    static GROUP_NAMES: RefCell<HashMap<(&'static str, &'static str, &'static str), &'static str>> =
+        RefCell::new(HashMap::new());
+    static GROUP_SUFFIXES: RefCell<HashMap<(&'static str, usize), &'static str>> =
+        RefCell::new(HashMap::new());
+}
+
+impl ParamGroup {
+    /// A group nested in this one. Prefixes are combined, and this group's
+    /// page takes precedence.
+    pub fn nested(&self, prefix: &'static str, page: Option<&'static str>) -> ParamGroup {
+        ParamGroup {
+            prefix: self.join(prefix),
+            suffix: self.suffix,
+            page: self.page.or(page),
+        }
+    }
+
+    /// The group of the element at `index` of a sequence in this group. Its
+    /// parameters are numbered from 1 by position, so the names of the
+    /// first elements don't change with the length of the sequence.
+    pub fn element(
+        &self,
+        prefix: &'static str,
+        index: usize,
+        page: Option<&'static str>,
+    ) -> ParamGroup {
+        let suffix = GROUP_SUFFIXES.with(|suffixes| {
+            *suffixes
+                .borrow_mut()
+                .entry((self.suffix, index))
+                .or_insert_with(|| {
+                    Box::leak(format!("{}{}", self.suffix, index + 1).into_boxed_str())
+                })
+        });
+        ParamGroup {
+            suffix,
+            ..self.nested(prefix, page)
+        }
+    }
+
+    /// The name of a parameter in this group. As TouchDesigner only allows
+    /// a capital first letter, the name is lowercased after the prefix.
+    /// Names are interned, so only the first lookup of each name allocates.
+    pub fn name(&self, name: &'static str) -> &'static str {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            return name;
+        }
+        GROUP_NAMES.with(|names| {
+            *names
+                .borrow_mut()
+                .entry((self.prefix, self.suffix, name))
+                .or_insert_with(|| {
+                    let name = if self.prefix.is_empty() {
+                        format!("{}{}", name, self.suffix)
+                    } else {
+                        format!(
+                            "{}{}{}",
+                            self.prefix,
+                            name.to_ascii_lowercase(),
+                            self.suffix
+                        )
+                    };
+                    Box::leak(name.into_boxed_str())
+                })
+        })
+    }
+
+    // The prefix of a group nested in this one, which doesn't take the
+    // suffix.
+    fn join(&self, prefix: &'static str) -> &'static str {
+        ParamGroup {
+            prefix: self.prefix,
+            suffix: "",
+            page: None,
+        }
+        .name(prefix)
+    }
+
+    /// The page of a parameter in this group.
+    pub fn page<'a>(&self, page: &'a str) -> &'a str {
+        self.page.unwrap_or(page)
+    }
+}
+
+/// The parameter names of a `#[derive(Params)]` struct, which are checked
+/// for duplicates at compile time.
+pub trait ParamNames {
+    const PARAM_NAMES: &'static [ParamName];
+}
+
+/// A parameter name, the names of a flattened group with their prefix, or
+/// the names of each element of a sequence with their prefix and the number
+/// of elements.
+#[derive(Debug, Copy, Clone)]
+pub enum ParamName {
+    Name(&'static str),
+    Group(&'static str, &'static [ParamName]),
+    Sequence(&'static str, usize, &'static [ParamName]),
+}
+
+const MAX_GROUP_DEPTH: usize = 8;
+
+/// Panic if two parameters, including those of flattened groups and
+/// sequences, have the same name. Called in a constant by
+/// `#[derive(Params)]`.
+pub const fn check_param_names(names: &'static [ParamName]) {
+    let count = name_count(names);
+    let mut i = 0;
+    while i < count {
+        let name = full_name(names, i);
+        let mut j = i + 1;
+        while j < count {
+            if name.eq(&full_name(names, j)) {
+                panic!("duplicate parameter name in a flattened group or sequence");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+const fn name_count(names: &'static [ParamName]) -> usize {
+    let mut count = 0;
+    let mut i = 0;
+    while i < names.len() {
+        count += match names[i] {
+            ParamName::Name(_) => 1,
+            ParamName::Group(_, names) => name_count(names),
+            ParamName::Sequence(_, len, names) => len * name_count(names),
+        };
+        i += 1;
+    }
+    count
+}
+
+// The `index`th name, with the prefixes of the groups it is in.
+const fn full_name(names: &'static [ParamName], mut index: usize) -> FullName {
+    let mut i = 0;
+    while i < names.len() {
+        match names[i] {
+            ParamName::Name(name) => {
+                if index == 0 {
+                    return FullName {
+                        parts: [name; MAX_GROUP_DEPTH],
+                        len: 1,
+                        suffix: [0; MAX_SUFFIX_LEN],
+                        suffix_len: 0,
+                    };
+                }
+                index -= 1;
+            }
+            ParamName::Group(prefix, names) => {
+                let count = name_count(names);
+                if index < count {
+                    return full_name(names, index).prefixed(prefix);
+                }
+                index -= count;
+            }
+            ParamName::Sequence(prefix, len, names) => {
+                let count = name_count(names);
+                if index < len * count {
+                    return full_name(names, index % count)
+                        .prefixed(prefix)
+                        .numbered(index / count + 1);
+                }
+                index -= len * count;
+            }
+        }
+        i += 1;
+    }
+    panic!("parameter name index out of range")
+}
+
+const MAX_SUFFIX_LEN: usize = 32;
+
+/// A name made of a prefix for each group it is in and the numbers of the
+/// sequence elements it is in, which is compared as [`ParamGroup::name`]
+/// joins them.
+struct FullName {
+    parts: [&'static str; MAX_GROUP_DEPTH],
+    len: usize,
+    suffix: [u8; MAX_SUFFIX_LEN],
+    suffix_len: usize,
+}
+
+impl FullName {
+    const fn prefixed(mut self, prefix: &'static str) -> Self {
+        if prefix.is_empty() {
+            return self;
+        }
+        if self.len == MAX_GROUP_DEPTH {
+            panic!("parameter groups are nested too deeply");
+        }
+        let mut i = self.len;
+        while i > 0 {
+            self.parts[i] = self.parts[i - 1];
+            i -= 1;
+        }
+        self.parts[0] = prefix;
+        self.len += 1;
+        self
+    }
+
+    // Outer sequences are numbered first, so the number is put before
+    // those of the sequences inside it.
+    const fn numbered(mut self, number: usize) -> Self {
+        let mut digits = 1;
+        while number / 10usize.pow(digits) > 0 {
+            digits += 1;
+        }
+        let digits = digits as usize;
+        if self.suffix_len + digits > MAX_SUFFIX_LEN {
+            panic!("parameter sequences are nested too deeply");
+        }
+        let mut i = self.suffix_len;
+        while i > 0 {
+            self.suffix[i - 1 + digits] = self.suffix[i - 1];
+            i -= 1;
+        }
+        let mut rest = number;
+        let mut i = digits;
+        while i > 0 {
+            self.suffix[i - 1] = b'0' + (rest % 10) as u8;
+            rest /= 10;
+            i -= 1;
+        }
+        self.suffix_len += digits;
+        self
+    }
+
+    const fn byte(&self, mut index: usize) -> Option<u8> {
+        let mut part = 0;
+        while part < self.len {
+            let bytes = self.parts[part].as_bytes();
+            if index < bytes.len() {
+                return Some(if part > 0 {
+                    bytes[index].to_ascii_lowercase()
+                } else {
+                    bytes[index]
+                });
+            }
+            index -= bytes.len();
+            part += 1;
+        }
+        if index < self.suffix_len {
+            return Some(self.suffix[index]);
+        }
+        None
+    }
+
+    const fn eq(&self, other: &FullName) -> bool {
+        let mut i = 0;
+        loop {
+            match (self.byte(i), other.byte(i)) {
+                (Some(a), Some(b)) if a == b => i += 1,
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// A description of a parameter declared by an [`OperatorParams`] struct,
+/// which can be read without running inside TouchDesigner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSchema {
+    /// The Rust field the parameter is read into.
+    pub field: String,
+    /// The name of the parameter.
+    pub name: String,
+    /// The label of the parameter.
+    pub label: String,
+    /// The page of the parameter.
+    pub page: String,
+    /// The kind of the parameter, with its ranges, defaults and menu entries.
+    pub descriptor: ParameterDescriptor,
+    /// The tooltip of the parameter.
+    pub help: Option<String>,
+    /// Whether a section starts at the parameter.
+    pub section: bool,
+    /// Whether the parameter can't be edited.
+    pub readonly: bool,
+    /// Whether the parameter isn't registered with TouchDesigner.
+    pub hidden: bool,
+}
+
+impl ParamSchema {
+    pub fn new(field: &str, options: &ParamOptions, descriptor: ParameterDescriptor) -> Self {
+        Self {
+            field: field.to_string(),
+            name: options.name.clone(),
+            label: options.label.clone(),
+            page: options.page.clone(),
+            descriptor,
+            help: options.help.clone(),
+            section: options.section,
+            readonly: options.readonly,
+            hidden: options.hidden,
+        }
+    }
+
+    /// Get the schema of `T`, building it with `describe` the first time it
+    /// is requested. Used by `#[derive(Params)]`.
+    pub fn cached<T: 'static>(describe: impl FnOnce() -> Vec<ParamSchema>) -> &'static [Self] {
+        static SCHEMAS: Mutex<BTreeMap<TypeId, &'static [ParamSchema]>> =
+            Mutex::new(BTreeMap::new());
+        let lock = || SCHEMAS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(schema) = lock().get(&TypeId::of::<T>()) {
+            return schema;
+        }
+        // Describing a type describes the types of its flattened fields, so
+        // the lock isn't held while building the schema. Should two threads
+        // race to build it, the first one inserted is kept.
+        let schema = describe();
+        lock()
+            .entry(TypeId::of::<T>())
+            // Built once per type, so leaking it is bounded.
+            .or_insert_with(|| Box::leak(schema.into_boxed_slice()))
+    }
 }
 
 impl From<NumericParameter> for cxx::OP_NumericParameter {
@@ -103,6 +568,210 @@ impl From<StringParameter> for cxx::OP_StringParameter {
     }
 }
 
+/// The range and default values of a numeric parameter, one per component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumericOptions {
+    /// The default values of the parameter.
+    pub default_values: [f64; 4],
+    /// The minimum values of the parameter.
+    pub min_values: [f64; 4],
+    /// The maximum values of the parameter.
+    pub max_values: [f64; 4],
+    /// Whether to clamp the minimum values of the parameter.
+    pub clamp_mins: [bool; 4],
+    /// Whether to clamp the maximum values of the parameter.
+    pub clamp_maxes: [bool; 4],
+    /// The minimum slider values of the parameter.
+    pub min_sliders: [f64; 4],
+    /// The maximum slider values of the parameter.
+    pub max_sliders: [f64; 4],
+}
+
+impl Default for NumericOptions {
+    fn default() -> Self {
+        Self {
+            default_values: [0.0; 4],
+            min_values: [0.0; 4],
+            max_values: [1.0; 4],
+            clamp_mins: [false; 4],
+            clamp_maxes: [false; 4],
+            min_sliders: [0.0; 4],
+            max_sliders: [1.0; 4],
+        }
+    }
+}
+
+/// An entry of a menu parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuEntry {
+    /// The name of the entry, which is the value of the parameter.
+    pub name: String,
+    /// The label of the entry shown in the menu.
+    pub label: String,
+}
+
+impl MenuEntry {
+    pub fn new(name: impl Into<String>, label: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            label: label.into(),
+        }
+    }
+}
+
+impl From<&str> for MenuEntry {
+    fn from(name: &str) -> Self {
+        Self::new(name, name)
+    }
+}
+
+impl From<String> for MenuEntry {
+    fn from(name: String) -> Self {
+        Self::new(name.clone(), name)
+    }
+}
+
+/// The family of operators an operator reference parameter accepts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OpFamily {
+    /// Any operator.
+    Any,
+    Chop,
+    Sop,
+    Top,
+    Dat,
+    Comp,
+    Mat,
+    /// An object COMP.
+    Object,
+    /// A panel COMP.
+    PanelComp,
+}
+
+/// The kind of a parameter, along with the options specific to that kind.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterDescriptor {
+    /// A float parameter with `size` components.
+    Float {
+        size: usize,
+        options: NumericOptions,
+    },
+    /// An integer parameter with `size` components.
+    Int {
+        size: usize,
+        options: NumericOptions,
+    },
+    /// A float parameter with x and y components.
+    Xy(NumericOptions),
+    /// A float parameter with x, y and z components.
+    Xyz(NumericOptions),
+    /// A float parameter with u and v components.
+    Uv(NumericOptions),
+    /// A float parameter with u, v and w components.
+    Uvw(NumericOptions),
+    /// A float parameter with width and height components.
+    Wh(NumericOptions),
+    /// A color parameter with r, g and b components.
+    Rgb(NumericOptions),
+    /// A color parameter with r, g, b and a components.
+    Rgba(NumericOptions),
+    /// An on/off toggle.
+    Toggle { default: bool },
+    /// A button which is pressed once.
+    Pulse,
+    /// A button which is on while held.
+    Momentary,
+    /// A menu whose value is the index of the selected entry.
+    Menu {
+        default: String,
+        entries: Vec<MenuEntry>,
+    },
+    /// A string with a menu of suggested entries.
+    StringMenu {
+        default: String,
+        entries: Vec<MenuEntry>,
+    },
+    /// A string.
+    String { default: String },
+    /// A path to a file.
+    File { default: String },
+    /// A path to a folder.
+    Folder { default: String },
+    /// A reference to an operator of the given family.
+    OpRef { family: OpFamily, default: String },
+    /// A header, which is a label with no value.
+    Header,
+    /// A python expression.
+    Python { default: String },
+}
+
+impl ParameterDescriptor {
+    /// The range and defaults of a numeric parameter, or `None` for other
+    /// kinds.
+    pub fn numeric(&self) -> Option<&NumericOptions> {
+        match self {
+            ParameterDescriptor::Float { options, .. }
+            | ParameterDescriptor::Int { options, .. }
+            | ParameterDescriptor::Xy(options)
+            | ParameterDescriptor::Xyz(options)
+            | ParameterDescriptor::Uv(options)
+            | ParameterDescriptor::Uvw(options)
+            | ParameterDescriptor::Wh(options)
+            | ParameterDescriptor::Rgb(options)
+            | ParameterDescriptor::Rgba(options) => Some(options),
+            _ => None,
+        }
+    }
+
+    /// The entries of a menu parameter, which are empty for other kinds.
+    pub fn entries(&self) -> &[MenuEntry] {
+        match self {
+            ParameterDescriptor::Menu { entries, .. }
+            | ParameterDescriptor::StringMenu { entries, .. } => entries,
+            _ => &[],
+        }
+    }
+}
+
+/// A parameter to register with TouchDesigner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    /// The name of the parameter.
+    pub name: String,
+    /// The label of the parameter.
+    pub label: String,
+    /// The page of the parameter.
+    pub page: String,
+    /// The kind of the parameter.
+    pub descriptor: ParameterDescriptor,
+}
+
+impl Parameter {
+    fn numeric(&self, options: &NumericOptions) -> NumericParameter {
+        NumericParameter {
+            name: self.name.clone(),
+            label: self.label.clone(),
+            page: self.page.clone(),
+            default_values: options.default_values,
+            min_values: options.min_values,
+            max_values: options.max_values,
+            clamp_mins: options.clamp_mins,
+            clamp_maxes: options.clamp_maxes,
+            min_sliders: options.min_sliders,
+            max_sliders: options.max_sliders,
+        }
+    }
+
+    fn string(&self, default: &str) -> StringParameter {
+        StringParameter {
+            name: self.name.clone(),
+            label: self.label.clone(),
+            page: self.page.clone(),
+            default_value: default.to_string(),
+        }
+    }
+}
+
 /// Manager for registering parameters with TouchDesigner.
 pub struct ParameterManager<'execute> {
     manager: Pin<&'execute mut crate::cxx::OP_ParameterManager>,
@@ -217,36 +886,92 @@ impl<'execute> ParameterManager<'execute> {
         self.manager.as_mut().appendObject(&param);
     }
 
+    /// Append a parameter described by a [`ParameterDescriptor`].
+    pub fn append(&mut self, param: Parameter) {
+        match &param.descriptor {
+            ParameterDescriptor::Float { size, options } => {
+                let numeric = param.numeric(options).into();
+                self.manager.as_mut().appendFloat(&numeric, *size as i32);
+            }
+            ParameterDescriptor::Int { size, options } => {
+                let numeric = param.numeric(options).into();
+                self.manager.as_mut().appendInt(&numeric, *size as i32);
+            }
+            ParameterDescriptor::Xy(options) => self.append_xy(param.numeric(options)),
+            ParameterDescriptor::Xyz(options) => self.append_xyz(param.numeric(options)),
+            ParameterDescriptor::Uv(options) => self.append_uv(param.numeric(options)),
+            ParameterDescriptor::Uvw(options) => self.append_uvw(param.numeric(options)),
+            ParameterDescriptor::Wh(options) => self.append_wh(param.numeric(options)),
+            ParameterDescriptor::Rgb(options) => self.append_rgb(param.numeric(options)),
+            ParameterDescriptor::Rgba(options) => self.append_rgba(param.numeric(options)),
+            ParameterDescriptor::Toggle { default } => {
+                let mut numeric = param.numeric(&NumericOptions::default());
+                numeric.default_values[0] = *default as usize as f64;
+                self.append_toggle(numeric);
+            }
+            ParameterDescriptor::Pulse => {
+                self.append_pulse(param.numeric(&NumericOptions::default()))
+            }
+            ParameterDescriptor::Momentary => {
+                self.append_momentary(param.numeric(&NumericOptions::default()))
+            }
+            ParameterDescriptor::Menu { default, entries } => {
+                let (names, labels) = menu_entries(entries);
+                self.append_menu(param.string(default), &names, &labels);
+            }
+            ParameterDescriptor::StringMenu { default, entries } => {
+                let (names, labels) = menu_entries(entries);
+                self.append_string_menu(param.string(default), &names, &labels);
+            }
+            ParameterDescriptor::String { default } => self.append_string(param.string(default)),
+            ParameterDescriptor::File { default } => self.append_file(param.string(default)),
+            ParameterDescriptor::Folder { default } => self.append_folder(param.string(default)),
+            ParameterDescriptor::OpRef { family, default } => {
+                let string = param.string(default);
+                match family {
+                    OpFamily::Any => self.append_op(string),
+                    OpFamily::Chop => self.append_chop(string),
+                    OpFamily::Sop => self.append_sop(string),
+                    OpFamily::Top => self.append_top(string),
+                    OpFamily::Dat => self.append_dat(string),
+                    OpFamily::Comp => self.append_comp(string),
+                    OpFamily::Mat => self.append_mat(string),
+                    OpFamily::Object => self.append_object(string),
+                    OpFamily::PanelComp => self.append_panel_comp(string),
+                }
+            }
+            ParameterDescriptor::Header => self.append_header(param.string("")),
+            ParameterDescriptor::Python { default } => self.append_python(param.string(default)),
+        }
+    }
+
+    /// Append a menu parameter, whose value is the index of the selected
+    /// entry.
     pub fn append_menu(&mut self, param: StringParameter, names: &[String], labels: &[String]) {
         assert_eq!(names.len(), labels.len());
-        let n_items = names.len() as i32;
-        let c_strings: Vec<CString> = names
-            .iter()
-            .map(|s| CString::new(s.as_bytes()).unwrap())
-            .collect();
-
-        let name_ptrs: Vec<*const c_char> = c_strings.iter().map(|cs| cs.as_ptr()).collect();
-        let names: *mut *const c_char = name_ptrs.as_ptr() as *mut *const c_char;
-
-        let c_strings: Vec<CString> = labels
-            .iter()
-            .map(|s| CString::new(s.as_bytes()).unwrap())
-            .collect();
-
-        let label_ptrs: Vec<*const c_char> = c_strings.iter().map(|cs| cs.as_ptr()).collect();
-        let labels: *mut *const c_char = label_ptrs.as_ptr() as *mut *const c_char;
-
         let param = param.into();
-        unsafe {
+        with_menu_items(names, labels, |n_items, names, labels| unsafe {
             self.manager
                 .as_mut()
                 .appendMenu(&param, n_items, names, labels);
-        }
+        });
     }
 
-    // pub fn append_string_menu(&mut self, param: StringParameter, names: &[&str], labels: &[&str]) {
-    //     self.manager.as_mut().appendStringMenu(&param, names.len(), names.as_ptr(), labels.as_ptr());
-    // }
+    /// Append a string parameter with a menu of suggested entries.
+    pub fn append_string_menu(
+        &mut self,
+        param: StringParameter,
+        names: &[String],
+        labels: &[String],
+    ) {
+        assert_eq!(names.len(), labels.len());
+        let param = param.into();
+        with_menu_items(names, labels, |n_items, names, labels| unsafe {
+            self.manager
+                .as_mut()
+                .appendStringMenu(&param, n_items, names, labels);
+        });
+    }
 
     /// Append a sop reference parameter.
     pub fn append_sop(&mut self, param: StringParameter) {
@@ -303,9 +1028,43 @@ impl<'execute> ParameterManager<'execute> {
     }
 }
 
+fn menu_entries(entries: &[MenuEntry]) -> (Vec<String>, Vec<String>) {
+    entries
+        .iter()
+        .map(|entry| (entry.name.clone(), entry.label.clone()))
+        .unzip()
+}
+
+// Call `f` with null terminated copies of menu names and labels, which are
+// only valid for the duration of the call.
+fn with_menu_items(
+    names: &[String],
+    labels: &[String],
+    f: impl FnOnce(i32, *mut *const c_char, *mut *const c_char),
+) {
+    let n_items = names.len() as i32;
+    let c_strings: Vec<CString> = names
+        .iter()
+        .map(|s| CString::new(s.as_bytes()).unwrap())
+        .collect();
+
+    let name_ptrs: Vec<*const c_char> = c_strings.iter().map(|cs| cs.as_ptr()).collect();
+    let names: *mut *const c_char = name_ptrs.as_ptr() as *mut *const c_char;
+
+    let c_strings: Vec<CString> = labels
+        .iter()
+        .map(|s| CString::new(s.as_bytes()).unwrap())
+        .collect();
+
+    let label_ptrs: Vec<*const c_char> = c_strings.iter().map(|cs| cs.as_ptr()).collect();
+    let labels: *mut *const c_char = label_ptrs.as_ptr() as *mut *const c_char;
+
+    f(n_items, names, labels);
+}
+
 /// Options for creating parameters in derive macro.
 /// Not intended for direct use.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParamOptions {
     pub name: String,
     pub label: String,
@@ -316,6 +1075,94 @@ pub struct ParamOptions {
     pub max_slider: f64,
     pub clamp: bool,
     pub default: f64,
+    pub style: Option<ParamStyle>,
+    /// The tooltip, which the SDK has no field for, so it is only recorded
+    /// in the schema.
+    pub help: Option<String>,
+    /// Whether a section starts at the parameter, which is only recorded in
+    /// the schema.
+    pub section: bool,
+    /// Whether the parameter is disabled so it can't be edited.
+    pub readonly: bool,
+    /// Whether the field is kept out of TouchDesigner, and only saved in
+    /// presets.
+    pub hidden: bool,
+}
+
+/// The widget used for a float parameter with several components, chosen
+/// with `#[param(style = "...")]`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParamStyle {
+    Xy,
+    Xyz,
+    Uv,
+    Uvw,
+    Wh,
+    Rgb,
+    Rgba,
+}
+
+impl ParamStyle {
+    /// The number of components of parameters with this style.
+    pub const fn size(&self) -> usize {
+        match self {
+            ParamStyle::Xy | ParamStyle::Uv | ParamStyle::Wh => 2,
+            ParamStyle::Xyz | ParamStyle::Uvw | ParamStyle::Rgb => 3,
+            ParamStyle::Rgba => 4,
+        }
+    }
+}
+
+impl ParamOptions {
+    /// The numeric range and default of these options.
+    pub fn numeric(&self) -> NumericOptions {
+        NumericOptions {
+            default_values: [self.default; 4],
+            min_values: [self.min; 4],
+            max_values: [self.max; 4],
+            clamp_mins: [self.clamp; 4],
+            clamp_maxes: [self.clamp; 4],
+            min_sliders: [self.min_slider; 4],
+            max_sliders: [self.max_slider; 4],
+        }
+    }
+
+    /// Describe a float parameter with `size` components, using the style of
+    /// these options if it has that many components. Otherwise pairs and
+    /// triples are shown as xy and xyz.
+    pub fn float_descriptor(&self, size: usize, numeric: NumericOptions) -> ParameterDescriptor {
+        let style = match self.style {
+            Some(style) if style.size() == size => Some(style),
+            _ => match size {
+                2 => Some(ParamStyle::Xy),
+                3 => Some(ParamStyle::Xyz),
+                _ => None,
+            },
+        };
+        match style {
+            Some(ParamStyle::Xy) => ParameterDescriptor::Xy(numeric),
+            Some(ParamStyle::Xyz) => ParameterDescriptor::Xyz(numeric),
+            Some(ParamStyle::Uv) => ParameterDescriptor::Uv(numeric),
+            Some(ParamStyle::Uvw) => ParameterDescriptor::Uvw(numeric),
+            Some(ParamStyle::Wh) => ParameterDescriptor::Wh(numeric),
+            Some(ParamStyle::Rgb) => ParameterDescriptor::Rgb(numeric),
+            Some(ParamStyle::Rgba) => ParameterDescriptor::Rgba(numeric),
+            None => ParameterDescriptor::Float {
+                size,
+                options: numeric,
+            },
+        }
+    }
+
+    /// Describe a parameter with the name, label and page of these options.
+    pub fn parameter(self, descriptor: ParameterDescriptor) -> Parameter {
+        Parameter {
+            name: self.name,
+            label: self.label,
+            page: self.page,
+            descriptor,
+        }
+    }
 }
 
 impl From<ParamOptions> for NumericParameter {
@@ -348,23 +1195,131 @@ impl From<ParamOptions> for StringParameter {
 
 /// Trait for implementing parameter types.
 pub trait Param {
-    /// Register parameter with the parameter manager.
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager);
+    /// Register parameter with the parameter manager, with this value as its
+    /// default.
+    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
+        let descriptor = self.descriptor(&options);
+        parameter_manager.append(options.parameter(descriptor));
+    }
     /// Update parameter from operator input.
     fn update(&mut self, name: &str, inputs: &ParamInputs);
+    /// Describe the parameter registered for `options`, with this value as
+    /// its default.
+    fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+        Self::describe(options)
+    }
+    /// Describe the parameter registered for `options` without a value,
+    /// using the defaults from the options.
+    fn describe(options: &ParamOptions) -> ParameterDescriptor;
+    /// The value saved for the parameter in a [`Preset`], or `None` if it
+    /// isn't saved in presets.
+    fn preset_value(&self) -> Option<PresetValue> {
+        None
+    }
+    /// Set the parameter named `name` from a preset, failing if the value
+    /// is the wrong kind or doesn't fit in the parameter's type.
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        Err(PresetError::wrong_type(name, "no value", value))
+    }
+    /// The range of values the type can hold, used as the min and max of
+    /// its parameter unless they are given. Integer parameters are 32 bit,
+    /// so wider types are limited to the range of `i32`.
+    fn type_range() -> Option<(f64, f64)>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// Convert a preset number to an integer, rejecting fractions and values
+/// that don't fit in `T`, whose range is given for the error.
+fn preset_int<T: TryFrom<i128>>(
+    name: &str,
+    value: f64,
+    range: (f64, f64),
+) -> Result<T, PresetError> {
+    if !value.is_finite() || value.fract() != 0.0 {
+        return Err(PresetError::WrongType {
+            name: name.to_string(),
+            expected: "a whole number",
+            found: if value.is_finite() {
+                "a fraction"
+            } else {
+                "a non-finite number"
+            },
+        });
+    }
+    T::try_from(value as i128).map_err(|_| PresetError::OutOfRange {
+        name: name.to_string(),
+        value,
+        min: range.0,
+        max: range.1,
+    })
 }
 
 macro_rules! impl_param_int {
     ( $t:ty ) => {
         impl Param for $t {
-            fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-                let mut param: NumericParameter = options.into();
-                param.default_values = [*self as f64, 0.0, 0.0, 0.0];
-                parameter_manager.append_int(param);
+            fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+                let mut numeric = options.numeric();
+                numeric.default_values = [*self as f64, 0.0, 0.0, 0.0];
+                ParameterDescriptor::Int {
+                    size: 1,
+                    options: numeric,
+                }
             }
 
             fn update(&mut self, name: &str, inputs: &ParamInputs) {
-                *self = inputs.get_int(name, 0) as $t;
+                let value = inputs.get_int(name, 0);
+                *self = match <$t>::try_from(value) {
+                    Ok(value) => value,
+                    Err(_) => {
+                        crate::messages::warn_once(format!(
+                            "{} is out of range for {}, which is a {}",
+                            value,
+                            name,
+                            stringify!($t)
+                        ));
+                        if value < 0 {
+                            <$t>::MIN
+                        } else {
+                            <$t>::MAX
+                        }
+                    }
+                };
+            }
+
+            fn type_range() -> Option<(f64, f64)> {
+                Some((
+                    (<$t>::MIN as f64).max(i32::MIN as f64),
+                    (<$t>::MAX as f64).min(i32::MAX as f64),
+                ))
+            }
+
+            fn preset_value(&self) -> Option<PresetValue> {
+                Some(PresetValue::Number(*self as f64))
+            }
+
+            fn apply_preset_value(
+                &mut self,
+                name: &str,
+                value: &PresetValue,
+            ) -> Result<(), PresetError> {
+                match value {
+                    PresetValue::Number(value) => {
+                        *self = preset_int(name, *value, (<$t>::MIN as f64, <$t>::MAX as f64))?;
+                        Ok(())
+                    }
+                    _ => Err(PresetError::wrong_type(name, "a number", value)),
+                }
+            }
+
+            fn describe(options: &ParamOptions) -> ParameterDescriptor {
+                ParameterDescriptor::Int {
+                    size: 1,
+                    options: options.numeric(),
+                }
             }
         }
     };
@@ -386,15 +1341,43 @@ impl_param_int!(usize);
 macro_rules! impl_param_float {
     ( $t:ty ) => {
         impl Param for $t {
-            fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-                let mut param: NumericParameter = options.into();
-                param.default_values = [*self as f64, 0.0, 0.0, 0.0];
-                parameter_manager.append_float(param);
+            fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+                let mut numeric = options.numeric();
+                numeric.default_values = [*self as f64, 0.0, 0.0, 0.0];
+                ParameterDescriptor::Float {
+                    size: 1,
+                    options: numeric,
+                }
             }
 
             fn update(&mut self, name: &str, inputs: &ParamInputs) {
                 *self = inputs.get_float(name, 0) as $t;
             }
+
+            fn preset_value(&self) -> Option<PresetValue> {
+                Some(PresetValue::Number(*self as f64))
+            }
+
+            fn apply_preset_value(
+                &mut self,
+                name: &str,
+                value: &PresetValue,
+            ) -> Result<(), PresetError> {
+                match value {
+                    PresetValue::Number(value) => {
+                        *self = *value as $t;
+                        Ok(())
+                    }
+                    _ => Err(PresetError::wrong_type(name, "a number", value)),
+                }
+            }
+
+            fn describe(options: &ParamOptions) -> ParameterDescriptor {
+                ParameterDescriptor::Float {
+                    size: 1,
+                    options: options.numeric(),
+                }
+            }
         }
     };
 }
@@ -402,40 +1385,393 @@ macro_rules! impl_param_float {
 impl_param_float!(f32);
 impl_param_float!(f64);
 
-impl Param for String {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: StringParameter = options.into();
-        param.default_value = self.clone();
-        parameter_manager.append_string(param);
-    }
+macro_rules! impl_param_float_arr {
+    ( $t:ty, $n:literal ) => {
+        impl Param for [$t; $n] {
+            fn update(&mut self, name: &str, inputs: &ParamInputs) {
+                let values = inputs.get_double_arr::<$n>(name);
+                for (v, value) in self.iter_mut().zip(values) {
+                    *v = value as $t;
+                }
+            }
 
-    fn update(&mut self, name: &str, inputs: &ParamInputs) {
-        *self = inputs.get_string(name).to_string();
-    }
+            fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+                let mut numeric = options.numeric();
+                for (default, v) in numeric.default_values.iter_mut().zip(self) {
+                    *default = *v as f64;
+                }
+                options.float_descriptor($n, numeric)
+            }
+
+            fn describe(options: &ParamOptions) -> ParameterDescriptor {
+                options.float_descriptor($n, options.numeric())
+            }
+
+            fn preset_value(&self) -> Option<PresetValue> {
+                Some(PresetValue::Numbers(
+                    self.iter().map(|v| *v as f64).collect(),
+                ))
+            }
+
+            fn apply_preset_value(
+                &mut self,
+                name: &str,
+                value: &PresetValue,
+            ) -> Result<(), PresetError> {
+                match value {
+                    PresetValue::Numbers(values) if values.len() == $n => {
+                        for (v, value) in self.iter_mut().zip(values) {
+                            *v = *value as $t;
+                        }
+                        Ok(())
+                    }
+                    _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+                }
+            }
+        }
+
+        impl StyledParam for [$t; $n] {
+            const SIZE: usize = $n;
+        }
+    };
 }
 
-impl Param for rgb::RGB8 {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: NumericParameter = options.into();
-        param.default_values = [self.r as f64, self.g as f64, self.b as f64, 0.0];
-        parameter_manager.append_rgb(param);
-    }
+/// A parameter which can be shown with a [`ParamStyle`].
+#[diagnostic::on_unimplemented(
+    message = "`style` can't be used on `{Self}`",
+    note = "`style` can only be used on float parameters of two to four components"
+)]
+pub trait StyledParam: Param {
+    /// The number of components of the parameter.
+    const SIZE: usize;
+}
 
-    fn update(&mut self, name: &str, inputs: &ParamInputs) {
-        *self = rgb::RGB8::new(
-            inputs.get_int(name, 0) as u8,
-            inputs.get_int(name, 1) as u8,
-            inputs.get_int(name, 2) as u8,
-        );
+/// Panic if `style` has a different number of components than `T`. Called
+/// in a constant by `#[derive(Params)]`.
+pub const fn check_param_style<T: StyledParam>(style: ParamStyle) {
+    if style.size() != T::SIZE {
+        panic!("`style` has a different number of components than the parameter");
     }
 }
 
-impl Param for rgb::RGB16 {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: NumericParameter = options.into();
-        param.default_values = [self.r as f64, self.g as f64, self.b as f64, 0.0];
-        parameter_manager.append_rgb(param);
-    }
+impl_param_float_arr!(f32, 2);
+impl_param_float_arr!(f32, 3);
+impl_param_float_arr!(f32, 4);
+impl_param_float_arr!(f64, 2);
+impl_param_float_arr!(f64, 3);
+impl_param_float_arr!(f64, 4);
+
+macro_rules! impl_param_int_arr {
+    ( $t:ty, $n:literal ) => {
+        impl Param for [$t; $n] {
+            fn update(&mut self, name: &str, inputs: &ParamInputs) {
+                *self = inputs.get_int_arr::<$n>(name);
+            }
+
+            fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+                let mut numeric = options.numeric();
+                for (default, v) in numeric.default_values.iter_mut().zip(self) {
+                    *default = *v as f64;
+                }
+                ParameterDescriptor::Int {
+                    size: $n,
+                    options: numeric,
+                }
+            }
+
+            fn describe(options: &ParamOptions) -> ParameterDescriptor {
+                ParameterDescriptor::Int {
+                    size: $n,
+                    options: options.numeric(),
+                }
+            }
+
+            fn preset_value(&self) -> Option<PresetValue> {
+                Some(PresetValue::Numbers(
+                    self.iter().map(|v| *v as f64).collect(),
+                ))
+            }
+
+            fn apply_preset_value(
+                &mut self,
+                name: &str,
+                value: &PresetValue,
+            ) -> Result<(), PresetError> {
+                match value {
+                    PresetValue::Numbers(values) if values.len() == $n => {
+                        let range = (<$t>::MIN as f64, <$t>::MAX as f64);
+                        let mut ints = *self;
+                        for (v, value) in ints.iter_mut().zip(values) {
+                            *v = preset_int(name, *value, range)?;
+                        }
+                        *self = ints;
+                        Ok(())
+                    }
+                    _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+                }
+            }
+        }
+    };
+}
+
+impl_param_int_arr!(i32, 2);
+impl_param_int_arr!(i32, 3);
+impl_param_int_arr!(i32, 4);
+
+// Types which convert to and from one of the array parameters above.
+macro_rules! impl_param_via_arr {
+    ( $t:ty, $arr:ty ) => {
+        impl Param for $t {
+            fn update(&mut self, name: &str, inputs: &ParamInputs) {
+                let mut arr = <$arr>::from(*self);
+                Param::update(&mut arr, name, inputs);
+                *self = arr.into();
+            }
+
+            fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+                Param::descriptor(&<$arr>::from(*self), options)
+            }
+
+            fn describe(options: &ParamOptions) -> ParameterDescriptor {
+                <$arr as Param>::describe(options)
+            }
+
+            fn preset_value(&self) -> Option<PresetValue> {
+                Param::preset_value(&<$arr>::from(*self))
+            }
+
+            fn apply_preset_value(
+                &mut self,
+                name: &str,
+                value: &PresetValue,
+            ) -> Result<(), PresetError> {
+                let mut arr = <$arr>::from(*self);
+                Param::apply_preset_value(&mut arr, name, value)?;
+                *self = arr.into();
+                Ok(())
+            }
+        }
+    };
+    ( $t:ty, $arr:ty, styled ) => {
+        impl_param_via_arr!($t, $arr);
+
+        impl StyledParam for $t {
+            const SIZE: usize = <$arr as StyledParam>::SIZE;
+        }
+    };
+}
+
+impl_param_via_arr!((f32, f32), [f32; 2], styled);
+impl_param_via_arr!((f32, f32, f32), [f32; 3], styled);
+impl_param_via_arr!((f32, f32, f32, f32), [f32; 4], styled);
+impl_param_via_arr!((f64, f64), [f64; 2], styled);
+impl_param_via_arr!((f64, f64, f64), [f64; 3], styled);
+impl_param_via_arr!((f64, f64, f64, f64), [f64; 4], styled);
+impl_param_via_arr!((i32, i32), [i32; 2]);
+impl_param_via_arr!((i32, i32, i32), [i32; 3]);
+impl_param_via_arr!((i32, i32, i32, i32), [i32; 4]);
+
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::Vec2, [f32; 2], styled);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::Vec3, [f32; 3], styled);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::Vec4, [f32; 4], styled);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::DVec2, [f64; 2], styled);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::DVec3, [f64; 3], styled);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::DVec4, [f64; 4], styled);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::IVec2, [i32; 2]);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::IVec3, [i32; 3]);
+#[cfg(feature = "glam")]
+impl_param_via_arr!(glam::IVec4, [i32; 4]);
+
+impl Param for String {
+    fn descriptor(&self, _options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::String {
+            default: self.clone(),
+        }
+    }
+
+    fn update(&mut self, name: &str, inputs: &ParamInputs) {
+        // Reuse the existing buffer rather than allocating every cook
+        self.clear();
+        self.push_str(inputs.get_string(name));
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::String {
+            default: String::new(),
+        }
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::String(self.clone()))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::String(value) => {
+                self.clone_from(value);
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a string", value)),
+        }
+    }
+}
+
+/// A string parameter with a menu of entries provided by the plugin, such as
+/// available devices or files in a folder. The value is the name of the
+/// selected entry, and may also be any other string typed by the user.
+///
+/// Entries are registered when parameters are set up, so they must be set
+/// before then, e.g. in the operator's constructor or with
+/// `#[param(entries = "method")]`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct StringMenu {
+    value: String,
+    entries: Vec<MenuEntry>,
+}
+
+impl StringMenu {
+    pub fn new(entries: impl IntoIterator<Item = impl Into<MenuEntry>>) -> Self {
+        Self::default().with_entries(entries)
+    }
+
+    pub fn with_entries(mut self, entries: impl IntoIterator<Item = impl Into<MenuEntry>>) -> Self {
+        self.set_entries(entries);
+        self
+    }
+
+    /// Replace the entries of the menu.
+    pub fn set_entries(&mut self, entries: impl IntoIterator<Item = impl Into<MenuEntry>>) {
+        self.entries = entries.into_iter().map(Into::into).collect();
+    }
+
+    pub fn entries(&self) -> &[MenuEntry] {
+        &self.entries
+    }
+
+    /// The name of the selected entry.
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// The selected entry, or `None` if the value isn't one of the entries.
+    pub fn selected(&self) -> Option<&MenuEntry> {
+        self.entries.iter().find(|entry| entry.name == self.value)
+    }
+}
+
+impl AsRef<str> for StringMenu {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl From<&str> for StringMenu {
+    fn from(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl From<String> for StringMenu {
+    fn from(value: String) -> Self {
+        Self {
+            value,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl Param for StringMenu {
+    fn descriptor(&self, _options: &ParamOptions) -> ParameterDescriptor {
+        // Select the first entry unless a value was given
+        let default = match self.entries.first() {
+            Some(entry) if self.value.is_empty() => entry.name.clone(),
+            _ => self.value.clone(),
+        };
+        ParameterDescriptor::StringMenu {
+            default,
+            entries: self.entries.clone(),
+        }
+    }
+
+    fn update(&mut self, name: &str, inputs: &ParamInputs) {
+        self.value.clear();
+        self.value.push_str(inputs.get_string(name));
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::StringMenu {
+            default: String::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        self.value.preset_value()
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        self.value.apply_preset_value(name, value)
+    }
+}
+
+impl Param for rgb::RGB8 {
+    fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+        let mut numeric = options.numeric();
+        numeric.default_values = [self.r as f64, self.g as f64, self.b as f64, 0.0];
+        ParameterDescriptor::Rgb(numeric)
+    }
+
+    fn update(&mut self, name: &str, inputs: &ParamInputs) {
+        *self = rgb::RGB8::new(
+            inputs.get_int(name, 0) as u8,
+            inputs.get_int(name, 1) as u8,
+            inputs.get_int(name, 2) as u8,
+        );
+    }
+
+    fn describe(options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Rgb(options.numeric())
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::Numbers(vec![
+            self.r as f64,
+            self.g as f64,
+            self.b as f64,
+        ]))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::Numbers(values) if values.len() == 3 => {
+                let range = (u8::MIN as f64, u8::MAX as f64);
+                self.r = preset_int(name, values[0], range)?;
+                self.g = preset_int(name, values[1], range)?;
+                self.b = preset_int(name, values[2], range)?;
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+        }
+    }
+}
+
+impl Param for rgb::RGB16 {
+    fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+        let mut numeric = options.numeric();
+        numeric.default_values = [self.r as f64, self.g as f64, self.b as f64, 0.0];
+        ParameterDescriptor::Rgb(numeric)
+    }
 
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         *self = rgb::RGB16::new(
@@ -444,13 +1780,38 @@ impl Param for rgb::RGB16 {
             inputs.get_int(name, 2) as u16,
         );
     }
+
+    fn describe(options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Rgb(options.numeric())
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::Numbers(vec![
+            self.r as f64,
+            self.g as f64,
+            self.b as f64,
+        ]))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::Numbers(values) if values.len() == 3 => {
+                let range = (u16::MIN as f64, u16::MAX as f64);
+                self.r = preset_int(name, values[0], range)?;
+                self.g = preset_int(name, values[1], range)?;
+                self.b = preset_int(name, values[2], range)?;
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+        }
+    }
 }
 
 impl Param for rgb::RGBA8 {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: NumericParameter = options.into();
-        param.default_values = [self.r as f64, self.g as f64, self.b as f64, self.a as f64];
-        parameter_manager.append_rgba(param);
+    fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+        let mut numeric = options.numeric();
+        numeric.default_values = [self.r as f64, self.g as f64, self.b as f64, self.a as f64];
+        ParameterDescriptor::Rgba(numeric)
     }
 
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
@@ -461,13 +1822,40 @@ impl Param for rgb::RGBA8 {
             inputs.get_int(name, 3) as u8,
         );
     }
+
+    fn describe(options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Rgba(options.numeric())
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::Numbers(vec![
+            self.r as f64,
+            self.g as f64,
+            self.b as f64,
+            self.a as f64,
+        ]))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::Numbers(values) if values.len() == 4 => {
+                let range = (u8::MIN as f64, u8::MAX as f64);
+                self.r = preset_int(name, values[0], range)?;
+                self.g = preset_int(name, values[1], range)?;
+                self.b = preset_int(name, values[2], range)?;
+                self.a = preset_int(name, values[3], range)?;
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+        }
+    }
 }
 
 impl Param for rgb::RGBA16 {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: NumericParameter = options.into();
-        param.default_values = [self.r as f64, self.g as f64, self.b as f64, self.a as f64];
-        parameter_manager.append_rgba(param);
+    fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+        let mut numeric = options.numeric();
+        numeric.default_values = [self.r as f64, self.g as f64, self.b as f64, self.a as f64];
+        ParameterDescriptor::Rgba(numeric)
     }
 
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
@@ -478,11 +1866,45 @@ impl Param for rgb::RGBA16 {
             inputs.get_int(name, 3) as u16,
         );
     }
+
+    fn describe(options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Rgba(options.numeric())
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::Numbers(vec![
+            self.r as f64,
+            self.g as f64,
+            self.b as f64,
+            self.a as f64,
+        ]))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::Numbers(values) if values.len() == 4 => {
+                let range = (u16::MIN as f64, u16::MAX as f64);
+                self.r = preset_int(name, values[0], range)?;
+                self.g = preset_int(name, values[1], range)?;
+                self.b = preset_int(name, values[2], range)?;
+                self.a = preset_int(name, values[3], range)?;
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+        }
+    }
 }
 
 /// A parameter wrapping a `PathBuf` that will be registered as a folder parameter.
+#[derive(Default, Clone, Debug)]
 pub struct FolderParam(PathBuf);
 
+impl<T: Into<PathBuf>> From<T> for FolderParam {
+    fn from(path: T) -> Self {
+        Self(path.into())
+    }
+}
+
 impl Deref for FolderParam {
     type Target = PathBuf;
 
@@ -498,14 +1920,28 @@ impl DerefMut for FolderParam {
 }
 
 impl Param for FolderParam {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: StringParameter = options.into();
-        param.default_value = self.to_string_lossy().to_string();
-        parameter_manager.append_folder(param);
+    fn descriptor(&self, _options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Folder {
+            default: self.to_string_lossy().to_string(),
+        }
     }
 
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
-        self.0 = PathBuf::from(inputs.get_string(name));
+        Param::update(&mut self.0, name, inputs);
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        self.0.preset_value()
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        self.0.apply_preset_value(name, value)
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Folder {
+            default: String::new(),
+        }
     }
 }
 
@@ -513,6 +1949,12 @@ impl Param for FolderParam {
 #[derive(Default, Clone, Debug)]
 pub struct FileParam(PathBuf);
 
+impl<T: Into<PathBuf>> From<T> for FileParam {
+    fn from(path: T) -> Self {
+        Self(path.into())
+    }
+}
+
 impl Deref for FileParam {
     type Target = PathBuf;
 
@@ -528,51 +1970,126 @@ impl DerefMut for FileParam {
 }
 
 impl Param for FileParam {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: StringParameter = options.into();
-        param.default_value = self.to_string_lossy().to_string();
-        parameter_manager.append_file(param);
+    fn descriptor(&self, _options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::File {
+            default: self.to_string_lossy().to_string(),
+        }
     }
 
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
-        self.0 = PathBuf::from(inputs.get_string(name));
+        Param::update(&mut self.0, name, inputs);
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        self.0.preset_value()
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        self.0.apply_preset_value(name, value)
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::File {
+            default: String::new(),
+        }
     }
 }
 
 impl Param for PathBuf {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: StringParameter = options.into();
-        param.default_value = self.to_string_lossy().to_string();
-        parameter_manager.append_file(param);
+    fn descriptor(&self, _options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::File {
+            default: self.to_string_lossy().to_string(),
+        }
     }
 
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
-        *self = PathBuf::from(inputs.get_string(name));
+        let path = inputs.get_string(name);
+        if self.as_os_str() != path {
+            *self = PathBuf::from(path);
+        }
     }
-}
 
-impl Param for bool {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let mut param: NumericParameter = options.into();
-        param.default_values[0] = true as usize as f64;
-        parameter_manager.append_toggle(param);
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::String(self.to_string_lossy().to_string()))
     }
 
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::String(path) => {
+                *self = PathBuf::from(path);
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a string", value)),
+        }
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::File {
+            default: String::new(),
+        }
+    }
+}
+
+impl Param for bool {
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         *self = inputs.get_toggle(name);
     }
+
+    fn descriptor(&self, _options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Toggle { default: *self }
+    }
+
+    fn describe(options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Toggle {
+            default: options.default != 0.0,
+        }
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        Some(PresetValue::Bool(*self))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::Bool(value) => {
+                *self = *value;
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a toggle", value)),
+        }
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy)]
 pub struct Pulse;
 
 impl Param for Pulse {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param: NumericParameter = options.into();
-        parameter_manager.append_pulse(param);
+    fn update(&mut self, _name: &str, _inputs: &ParamInputs) {}
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Pulse
     }
+}
 
-    fn update(&mut self, _name: &str, _inputs: &ParamInputs) {}
+/// A button which is on while it is held.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Momentary(pub bool);
+
+impl Momentary {
+    /// Whether the button is held.
+    pub fn is_pressed(&self) -> bool {
+        self.0
+    }
+}
+
+impl Param for Momentary {
+    fn update(&mut self, name: &str, inputs: &ParamInputs) {
+        self.0 = inputs.get_toggle(name);
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Momentary
+    }
 }
 
 /// A chop parameter.
@@ -582,14 +2099,16 @@ pub struct ChopParam {
 }
 
 impl Param for ChopParam {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param: StringParameter = options.into();
-        parameter_manager.append_chop(param);
-    }
-
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         *self = inputs.get_chop(name);
     }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::OpRef {
+            family: OpFamily::Chop,
+            default: String::new(),
+        }
+    }
 }
 
 impl ChopParam {
@@ -606,14 +2125,16 @@ pub struct SopParam {
 }
 
 impl Param for SopParam {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param: StringParameter = options.into();
-        parameter_manager.append_sop(param);
-    }
-
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         *self = inputs.get_sop(name);
     }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::OpRef {
+            family: OpFamily::Sop,
+            default: String::new(),
+        }
+    }
 }
 
 impl SopParam {
@@ -630,14 +2151,16 @@ pub struct TopParam {
 }
 
 impl Param for TopParam {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param: StringParameter = options.into();
-        parameter_manager.append_top(param);
-    }
-
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         *self = inputs.get_top(name);
     }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::OpRef {
+            family: OpFamily::Top,
+            default: String::new(),
+        }
+    }
 }
 
 impl TopParam {
@@ -654,14 +2177,16 @@ pub struct DatParam {
 }
 
 impl Param for DatParam {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param: StringParameter = options.into();
-        parameter_manager.append_dat(param);
-    }
-
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         *self = inputs.get_dat(name);
     }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::OpRef {
+            family: OpFamily::Dat,
+            default: String::new(),
+        }
+    }
 }
 
 impl DatParam {
@@ -672,13 +2197,153 @@ impl DatParam {
     }
 }
 
-#[cfg(feature = "python")]
-impl Param for *mut pyo3_ffi::PyObject {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param = options.into();
-        parameter_manager.append_python(param);
+/// An object COMP parameter, giving access to the object's transforms.
+#[derive(Default, Debug, Clone)]
+pub struct ObjectParam {
+    pub(crate) name: String,
+    pub(crate) input: Option<*const cxx::OP_ObjectInput>,
+}
+
+impl Param for ObjectParam {
+    fn update(&mut self, name: &str, inputs: &ParamInputs) {
+        self.input = inputs.get_object(name);
+        if self.name != name {
+            self.name = name.to_string();
+        }
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::OpRef {
+            family: OpFamily::Object,
+            default: String::new(),
+        }
+    }
+}
+
+impl ObjectParam {
+    /// Get the object input for this parameter, if it exists.
+    pub fn input(&self) -> Option<&ObjectInput> {
+        self.input
+            .map(|input| unsafe { ObjectInput::ref_cast(&*input) })
+    }
+
+    /// Get the world transform of the object, if it exists.
+    pub fn world_transform(&self) -> Option<Transform> {
+        self.input().map(ObjectInput::world_transform)
     }
 
+    /// Get the local transform of the object, if it exists.
+    pub fn local_transform(&self) -> Option<Transform> {
+        self.input().map(ObjectInput::local_transform)
+    }
+}
+
+/// A COMP parameter. Object COMPs, such as geometry and camera COMPs, also
+/// give access to the object's transforms.
+#[derive(Default, Debug, Clone)]
+pub struct CompParam {
+    pub(crate) path: String,
+    pub(crate) object: Option<*const cxx::OP_ObjectInput>,
+}
+
+impl Param for CompParam {
+    fn update(&mut self, name: &str, inputs: &ParamInputs) {
+        update_path(&mut self.path, name, inputs);
+        self.object = inputs.get_object(name);
+    }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::OpRef {
+            family: OpFamily::Comp,
+            default: String::new(),
+        }
+    }
+}
+
+impl CompParam {
+    /// Get the path of the COMP, which is empty if none is set.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the object input for the COMP, if it is an object COMP.
+    pub fn object(&self) -> Option<&ObjectInput> {
+        self.object
+            .map(|input| unsafe { ObjectInput::ref_cast(&*input) })
+    }
+}
+
+macro_rules! impl_param_op_path {
+    ($(#[$meta:meta])* $t:ident, $family:expr) => {
+        $(#[$meta])*
+        ///
+        /// The SDK doesn't give access to the operator itself, only its path.
+        #[derive(Default, Debug, Clone, PartialEq, Eq)]
+        pub struct $t {
+            pub(crate) path: String,
+        }
+
+        impl Param for $t {
+            fn update(&mut self, name: &str, inputs: &ParamInputs) {
+                update_path(&mut self.path, name, inputs);
+            }
+
+            fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+                ParameterDescriptor::OpRef {
+                    family: $family,
+                    default: String::new(),
+                }
+            }
+        }
+
+        impl $t {
+            /// Get the path of the operator, which is empty if none is set.
+            pub fn path(&self) -> &str {
+                &self.path
+            }
+        }
+    };
+}
+
+impl_param_op_path!(
+    /// A parameter referencing an operator of any family.
+    OpParam,
+    OpFamily::Any
+);
+impl_param_op_path!(
+    /// A MAT parameter.
+    MatParam,
+    OpFamily::Mat
+);
+impl_param_op_path!(
+    /// A panel COMP parameter.
+    PanelCompParam,
+    OpFamily::PanelComp
+);
+
+// Only reallocate the path when it changes, as it is read every cook.
+fn update_path(path: &mut String, name: &str, inputs: &ParamInputs) {
+    let value = inputs.get_string(name);
+    if path != value {
+        *path = value.to_string();
+    }
+}
+
+/// A header, which labels the parameters below it on the page. The header's
+/// text is the parameter's label.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Header;
+
+impl Param for Header {
+    fn update(&mut self, _name: &str, _inputs: &ParamInputs) {}
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Header
+    }
+}
+
+#[cfg(feature = "python")]
+impl Param for *mut pyo3_ffi::PyObject {
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         // Ensure that the old object is decref'd
         unsafe {
@@ -686,6 +2351,12 @@ impl Param for *mut pyo3_ffi::PyObject {
         }
         *self = inputs.get_python(name);
     }
+
+    fn describe(_options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Python {
+            default: String::new(),
+        }
+    }
 }
 
 pub trait MenuParam {
@@ -694,13 +2365,83 @@ pub trait MenuParam {
 }
 
 impl Param for Color {
-    fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-        let param = options.into();
-        parameter_manager.append_rgba(param);
-    }
-
     fn update(&mut self, name: &str, inputs: &ParamInputs) {
         let [r, g, b, a] = inputs.get_double_arr::<4>(name);
         *self = (r, g, b, a).into();
     }
+
+    fn descriptor(&self, options: &ParamOptions) -> ParameterDescriptor {
+        let mut numeric = options.numeric();
+        numeric.default_values = [self.r as f64, self.g as f64, self.b as f64, self.a as f64];
+        ParameterDescriptor::Rgba(numeric)
+    }
+
+    fn describe(options: &ParamOptions) -> ParameterDescriptor {
+        ParameterDescriptor::Rgba(options.numeric())
+    }
+
+    fn preset_value(&self) -> Option<PresetValue> {
+        let values = [self.r, self.g, self.b, self.a];
+        Some(PresetValue::Numbers(
+            values.iter().map(|v| *v as f64).collect(),
+        ))
+    }
+
+    fn apply_preset_value(&mut self, name: &str, value: &PresetValue) -> Result<(), PresetError> {
+        match value {
+            PresetValue::Numbers(values) if values.len() == 4 => {
+                *self = (values[0], values[1], values[2], values[3]).into();
+                Ok(())
+            }
+            _ => Err(PresetError::wrong_type(name, "a list of numbers", value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ParamChanges, ParamGroup};
+
+    #[test]
+    fn test_group_name() {
+        assert_eq!(ParamGroup::default().name("Gain"), "Gain");
+        let group = ParamGroup::default().nested("Filter", None);
+        assert_eq!(group.name("Gain"), "Filtergain");
+    }
+
+    #[test]
+    fn test_nested_group_name() {
+        let group = ParamGroup::default()
+            .nested("Filter", None)
+            .nested("Low", None);
+        assert_eq!(group.name("Gain"), "Filterlowgain");
+    }
+
+    #[test]
+    fn test_nested_group_page() {
+        let group = ParamGroup::default().nested("Filter", Some("Filter"));
+        assert_eq!(group.page("Custom"), "Filter");
+        let nested = group.nested("Low", Some("Low"));
+        assert_eq!(nested.page("Custom"), "Filter");
+        assert_eq!(ParamGroup::default().page("Custom"), "Custom");
+    }
+
+    #[test]
+    fn test_changes_kept_until_end_of_cook() {
+        let mut changes = ParamChanges::default();
+        changes.begin(1);
+        changes.record(0, 1);
+        changes.end_cook();
+
+        // The operator updates its parameters more than once per cook.
+        changes.begin(1);
+        assert!(changes.record(0, 2));
+        changes.begin(1);
+        assert!(!changes.record(0, 2));
+        assert!(changes.is_changed(0));
+        assert!(!changes.is_updated(0));
+
+        changes.end_cook();
+        assert!(!changes.is_changed(0));
+    }
 }
@@ -0,0 +1,388 @@
+use std::collections::BTreeMap;
+use std::fmt::Formatter;
+
+use crate::ParamOptions;
+
+/// The value of a parameter in a [`Preset`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum PresetValue {
+    Bool(bool),
+    Number(f64),
+    Numbers(Vec<f64>),
+    String(String),
+}
+
+impl PresetValue {
+    /// The name of the kind of value, used in errors.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            PresetValue::Bool(_) => "a toggle",
+            PresetValue::Number(_) => "a number",
+            PresetValue::Numbers(_) => "a list of numbers",
+            PresetValue::String(_) => "a string",
+        }
+    }
+
+    /// The numeric components of the value, if it has any.
+    pub fn numbers(&self) -> &[f64] {
+        match self {
+            PresetValue::Number(number) => std::slice::from_ref(number),
+            PresetValue::Numbers(numbers) => numbers,
+            _ => &[],
+        }
+    }
+
+    /// Interpolate between numeric values of the same size. Other values
+    /// switch from `self` to `other` half way.
+    pub fn lerp(&self, other: &PresetValue, t: f64) -> PresetValue {
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        match (self, other) {
+            (PresetValue::Number(a), PresetValue::Number(b)) => PresetValue::Number(lerp(*a, *b)),
+            (PresetValue::Numbers(a), PresetValue::Numbers(b)) if a.len() == b.len() => {
+                PresetValue::Numbers(a.iter().zip(b).map(|(a, b)| lerp(*a, *b)).collect())
+            }
+            _ if t < 0.5 => self.clone(),
+            _ => other.clone(),
+        }
+    }
+}
+
+/// A snapshot of parameter values keyed by parameter name, taken with
+/// [`OperatorParams::to_preset`](crate::OperatorParams::to_preset) and
+/// applied with
+/// [`OperatorParams::apply_preset`](crate::OperatorParams::apply_preset).
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Preset {
+    values: BTreeMap<String, PresetValue>,
+}
+
+impl Preset {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PresetValue> {
+        self.values.get(name)
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, value: PresetValue) {
+        self.values.insert(name.into(), value);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<PresetValue> {
+        self.values.remove(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Iterate over the values in order of name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &PresetValue)> {
+        self.values
+            .iter()
+            .map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// The parameters whose values differ between `self` and `other`.
+    pub fn diff(&self, other: &Preset) -> Vec<PresetDiff> {
+        let mut diffs = Vec::new();
+        for (name, value) in &self.values {
+            match other.values.get(name) {
+                Some(other) if other == value => {}
+                other => diffs.push(PresetDiff {
+                    name: name.clone(),
+                    from: Some(value.clone()),
+                    to: other.cloned(),
+                }),
+            }
+        }
+        for (name, value) in &other.values {
+            if !self.values.contains_key(name) {
+                diffs.push(PresetDiff {
+                    name: name.clone(),
+                    from: None,
+                    to: Some(value.clone()),
+                });
+            }
+        }
+        diffs
+    }
+
+    /// Interpolate from `self` at `t = 0` to `other` at `t = 1`. Parameters
+    /// in only one of the presets are kept as they are.
+    pub fn lerp(&self, other: &Preset, t: f64) -> Preset {
+        let mut values = self.values.clone();
+        for (name, value) in &other.values {
+            let value = match self.values.get(name) {
+                Some(from) => from.lerp(value, t),
+                None => value.clone(),
+            };
+            values.insert(name.clone(), value);
+        }
+        Preset { values }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Preset {
+    pub fn to_json(&self) -> Result<String, PresetError> {
+        serde_json::to_string_pretty(self).map_err(|e| PresetError::Parse(e.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PresetError> {
+        serde_json::from_str(json).map_err(|e| PresetError::Parse(e.to_string()))
+    }
+
+    pub fn to_toml(&self) -> Result<String, PresetError> {
+        toml::to_string_pretty(self).map_err(|e| PresetError::Parse(e.to_string()))
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, PresetError> {
+        toml::from_str(toml).map_err(|e| PresetError::Parse(e.to_string()))
+    }
+
+    /// Parse a preset from JSON or TOML, depending on whether it starts with
+    /// a `{`.
+    pub fn parse(text: &str) -> Result<Self, PresetError> {
+        if text.trim_start().starts_with('{') {
+            Self::from_json(text)
+        } else {
+            Self::from_toml(text)
+        }
+    }
+
+    /// Load a preset from a `.toml` file, or a JSON file otherwise.
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, PresetError> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).map_err(|e| PresetError::Io(e.to_string()))?;
+        if is_toml(path) {
+            Self::from_toml(&text)
+        } else {
+            Self::from_json(&text)
+        }
+    }
+
+    /// Save a preset to a `.toml` file, or a JSON file otherwise.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), PresetError> {
+        let path = path.as_ref();
+        let text = if is_toml(path) {
+            self.to_toml()?
+        } else {
+            self.to_json()?
+        };
+        std::fs::write(path, text).map_err(|e| PresetError::Io(e.to_string()))
+    }
+
+    /// Load a preset from the text of the DAT referenced by a parameter.
+    pub fn from_dat(dat: &crate::DatParam) -> Result<Self, PresetError> {
+        match dat.input() {
+            Some(input) => Self::parse(input.text()),
+            None => Err(PresetError::Io(
+                "no DAT to load the preset from".to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn is_toml(path: &std::path::Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "toml")
+}
+
+/// A parameter whose value differs between two presets. A value is `None`
+/// if the parameter isn't in that preset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetDiff {
+    pub name: String,
+    pub from: Option<PresetValue>,
+    pub to: Option<PresetValue>,
+}
+
+/// A problem loading or applying a preset.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetError {
+    /// The preset has a value for a parameter which doesn't exist.
+    Unknown { name: String },
+    /// A value is outside the range of a clamped parameter.
+    OutOfRange {
+        name: String,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /// A value is the wrong kind for its parameter.
+    WrongType {
+        name: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The preset couldn't be read or written.
+    Io(String),
+    /// The preset couldn't be parsed or serialized.
+    Parse(String),
+}
+
+impl PresetError {
+    /// A [`PresetError::WrongType`] for `value` on a parameter expecting
+    /// `expected`.
+    pub fn wrong_type(name: &str, expected: &'static str, value: &PresetValue) -> PresetError {
+        PresetError::WrongType {
+            name: name.to_string(),
+            expected,
+            found: value.kind(),
+        }
+    }
+
+    /// Check that the numeric components of `value` are within the range of
+    /// a clamped parameter.
+    pub fn check_range(
+        name: &str,
+        value: &PresetValue,
+        options: &ParamOptions,
+    ) -> Result<(), PresetError> {
+        if !options.clamp {
+            return Ok(());
+        }
+        match value
+            .numbers()
+            .iter()
+            .find(|n| **n < options.min || **n > options.max)
+        {
+            Some(value) => Err(PresetError::OutOfRange {
+                name: name.to_string(),
+                value: *value,
+                min: options.min,
+                max: options.max,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for PresetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PresetError::Unknown { name } => write!(f, "unknown parameter {}", name),
+            PresetError::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} is out of range for {}, expected {} to {}",
+                value, name, min, max
+            ),
+            PresetError::WrongType {
+                name,
+                expected,
+                found,
+            } => write!(f, "expected {} for {}, found {}", expected, name, found),
+            PresetError::Io(message) | PresetError::Parse(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn preset(values: &[(&str, PresetValue)]) -> Preset {
+        let mut preset = Preset::new();
+        for (name, value) in values {
+            preset.insert(*name, value.clone());
+        }
+        preset
+    }
+
+    #[test]
+    fn test_diff() {
+        let a = preset(&[
+            ("Gain", PresetValue::Number(1.0)),
+            ("Mode", PresetValue::String("A".to_string())),
+            ("Old", PresetValue::Bool(true)),
+        ]);
+        let b = preset(&[
+            ("Gain", PresetValue::Number(2.0)),
+            ("Mode", PresetValue::String("A".to_string())),
+            ("New", PresetValue::Bool(false)),
+        ]);
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                PresetDiff {
+                    name: "Gain".to_string(),
+                    from: Some(PresetValue::Number(1.0)),
+                    to: Some(PresetValue::Number(2.0)),
+                },
+                PresetDiff {
+                    name: "Old".to_string(),
+                    from: Some(PresetValue::Bool(true)),
+                    to: None,
+                },
+                PresetDiff {
+                    name: "New".to_string(),
+                    from: None,
+                    to: Some(PresetValue::Bool(false)),
+                },
+            ]
+        );
+        assert!(a.diff(&a).is_empty());
+    }
+
+    #[test]
+    fn test_lerp_numbers() {
+        let a = preset(&[
+            ("Gain", PresetValue::Number(0.0)),
+            ("Color", PresetValue::Numbers(vec![0.0, 1.0])),
+        ]);
+        let b = preset(&[
+            ("Gain", PresetValue::Number(4.0)),
+            ("Color", PresetValue::Numbers(vec![1.0, 0.0])),
+        ]);
+        let lerped = a.lerp(&b, 0.25);
+        assert_eq!(lerped.get("Gain"), Some(&PresetValue::Number(1.0)));
+        assert_eq!(
+            lerped.get("Color"),
+            Some(&PresetValue::Numbers(vec![0.25, 0.75]))
+        );
+    }
+
+    #[test]
+    fn test_lerp_switches_half_way() {
+        let a = preset(&[
+            ("Mode", PresetValue::String("A".to_string())),
+            ("Size", PresetValue::Numbers(vec![0.0])),
+        ]);
+        let b = preset(&[
+            ("Mode", PresetValue::String("B".to_string())),
+            ("Size", PresetValue::Numbers(vec![1.0, 1.0])),
+        ]);
+        assert_eq!(a.lerp(&b, 0.4), a);
+        assert_eq!(a.lerp(&b, 0.5), b);
+    }
+
+    #[test]
+    fn test_lerp_keeps_unshared() {
+        let a = preset(&[("Old", PresetValue::Number(1.0))]);
+        let b = preset(&[("New", PresetValue::Number(2.0))]);
+        let lerped = a.lerp(&b, 0.5);
+        assert_eq!(lerped.get("Old"), Some(&PresetValue::Number(1.0)));
+        assert_eq!(lerped.get("New"), Some(&PresetValue::Number(2.0)));
+    }
+}
@@ -5,6 +5,7 @@ use crate::{cxx, GetInput, OperatorInputs};
 use auto_ops::impl_op_ex;
 use derive_more::{AsRef, Deref, DerefMut, From, Into};
 use ref_cast::RefCast;
+use std::pin::Pin;
 
 /// A sop input.
 #[repr(transparent)]
@@ -100,6 +101,70 @@ impl SopInput {
     pub fn num_vertices(&self) -> usize {
         self.input.getNumVertices() as usize
     }
+
+    /// Check whether a position is inside the geometry. The geometry must be
+    /// closed for the result to be meaningful.
+    pub fn is_inside(&self, pos: impl Into<Position>) -> bool {
+        let pos = pos.into();
+        cxx::sopInputIsInside(&self.input, &pos)
+    }
+
+    /// Cast a ray from `origin` along `dir` against the geometry, returning
+    /// the closest hit if there is one.
+    pub fn raycast(&self, origin: impl Into<Position>, dir: impl Into<Vec3>) -> Option<RayHit> {
+        let origin = origin.into();
+        let dir = dir.into();
+        let mut position = cxx::Position {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let mut distance = 0.0;
+        let mut normal = cxx::Vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let mut u = 0.0;
+        let mut v = 0.0;
+        let mut primitive_index = autocxx::c_int(0);
+        let hit = cxx::sopInputSendRay(
+            &self.input,
+            &origin,
+            &dir,
+            Pin::new(&mut position),
+            Pin::new(&mut distance),
+            Pin::new(&mut normal),
+            Pin::new(&mut u),
+            Pin::new(&mut v),
+            Pin::new(&mut primitive_index),
+        );
+        if !hit {
+            return None;
+        }
+        Some(RayHit {
+            position: Position(position),
+            distance,
+            normal: Vec3(normal),
+            uv: [u, v],
+            primitive_index: primitive_index.0 as usize,
+        })
+    }
+}
+
+/// The closest intersection of a ray cast against a sop input.
+#[derive(Clone)]
+pub struct RayHit {
+    /// The position of the hit.
+    pub position: Position,
+    /// The distance from the ray's origin to the hit.
+    pub distance: f32,
+    /// The normal of the geometry at the hit.
+    pub normal: Vec3,
+    /// The uv coordinates of the hit on the primitive.
+    pub uv: [f32; 2],
+    /// The index of the primitive that was hit.
+    pub primitive_index: usize,
 }
 
 #[derive(RefCast, Deref, DerefMut, AsRef, From, Into)]
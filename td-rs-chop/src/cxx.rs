@@ -11,9 +11,11 @@ use autocxx::subclass::*;
 pub use ffi::TD::*;
 pub use ffi::*;
 pub use td_rs_base::cxx::*;
-use td_rs_base::{NodeInfo, OperatorInputs, ParameterManager};
+use td_rs_base::{
+    ErrorOutput, NodeInfo, Op, OpMessages, OperatorInputs, PanicGuard, ParameterManager,
+};
 
-use crate::{Chop, ChopOutput};
+use crate::{Chop, ChopGeneralInfo, ChopInput, ChopOutput};
 
 include_cpp! {
     #include "CHOP_CPlusPlusBase.h"
@@ -45,6 +47,8 @@ extern "C" {
 #[subclass(superclass("RustChopPlugin"))]
 pub struct RustChopPluginImpl {
     pub inner: Box<dyn Chop>,
+    messages: OpMessages,
+    panic: PanicGuard,
 }
 
 // SAFETY: This can only be used with pointers returned from getNodeInstance() and
@@ -68,13 +72,34 @@ impl AsPlugin for RustChopPluginImplCpp {
 
 #[no_mangle]
 extern "C" fn chop_new(info: &'static OP_NodeInfo) -> *mut RustChopPluginImplCpp {
-    unsafe {
-        let info = NodeInfo::new(info);
-        RustChopPluginImpl::new_cpp_owned(RustChopPluginImpl {
-            inner: chop_new_impl(info),
-            cpp_peer: CppSubclassCppPeerHolder::Empty,
-        })
-        .into_raw()
+    let info = NodeInfo::new(info);
+    let mut messages = OpMessages::new();
+    let mut panic = PanicGuard::new();
+    let inner = panic.call(&mut messages, || unsafe { chop_new_impl(info) });
+    let inner = inner.unwrap_or_else(|| {
+        panic.set_fatal();
+        Box::new(FailedChop)
+    });
+    RustChopPluginImpl::new_cpp_owned(RustChopPluginImpl {
+        inner,
+        messages,
+        panic,
+        cpp_peer: CppSubclassCppPeerHolder::Empty,
+    })
+    .into_raw()
+}
+
+/// Stands in for a plugin whose constructor panicked, so the node is still
+/// created and shows the panic as its error.
+struct FailedChop;
+
+impl Op for FailedChop {}
+
+impl Chop for FailedChop {
+    fn execute(&mut self, _output: &mut ChopOutput, _input: &OperatorInputs<ChopInput>) {}
+
+    fn general_info(&self, _input: &OperatorInputs<ChopInput>) -> ChopGeneralInfo {
+        ChopGeneralInfo::default()
     }
 }
 
@@ -90,25 +115,45 @@ impl RustChopPlugin_methods for RustChopPluginImpl {
     fn getGeneralInfo(&mut self, mut info: Pin<&mut CHOP_GeneralInfo>, input: &OP_Inputs) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getGeneralInfo").entered() };
+        if !self.panic.is_poisoned() {
+            self.messages.clear();
+        }
         let input = OperatorInputs::new(input);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return;
+        }
+        let gen_info = self
+            .panic
+            .call(&mut self.messages, || self.inner.general_info(&input));
+        if let Some(gen_info) = gen_info {
+            info.cookEveryFrame = gen_info.cook_every_frame;
+            info.cookEveryFrameIfAsked = gen_info.cook_every_frame_if_asked;
+            info.timeslice = gen_info.timeslice;
+            info.inputMatchIndex = gen_info.input_match_index;
         }
-        let gen_info = self.inner.general_info(&input);
-        info.cookEveryFrame = gen_info.cook_every_frame;
-        info.cookEveryFrameIfAsked = gen_info.cook_every_frame_if_asked;
-        info.timeslice = gen_info.timeslice;
-        info.inputMatchIndex = gen_info.input_match_index;
     }
 
     fn getOutputInfo(&mut self, mut info: Pin<&mut CHOP_OutputInfo>, input: &OP_Inputs) -> bool {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getOutputInfo").entered() };
         let input = OperatorInputs::new(input);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return false;
         }
-        let out_info = self.inner.output_info(&input);
+        let out_info = self
+            .panic
+            .call(&mut self.messages, || self.inner.output_info(&input))
+            .flatten();
         if let Some(out_info) = out_info {
             info.numChannels = out_info.num_channels as i32;
             info.sampleRate = out_info.sample_rate;
@@ -123,55 +168,96 @@ impl RustChopPlugin_methods for RustChopPluginImpl {
     fn getChannelName(&mut self, index: i32, name: Pin<&mut OP_String>, input: &OP_Inputs) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getChannelName").entered() };
-        let input = OperatorInputs::new(input);
-        let chan_name = self.inner.channel_name(index as usize, &input);
-        unsafe {
-            let new_string = CString::new(chan_name.as_str()).unwrap();
-            let new_string_ptr = new_string.as_ptr();
-            name.setString(new_string_ptr);
+        if self.panic.is_poisoned() {
+            return;
         }
+        let input = OperatorInputs::new(input);
+        self.panic.call(&mut self.messages, || {
+            let chan_name = self.inner.channel_name(index as usize, &input);
+            unsafe {
+                let new_string = CString::new(chan_name.as_str()).unwrap();
+                let new_string_ptr = new_string.as_ptr();
+                name.setString(new_string_ptr);
+            }
+        });
     }
 
     fn execute(&mut self, output: Pin<&mut CHOP_Output>, input: &OP_Inputs) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("execute").entered() };
         let input = OperatorInputs::new(input);
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return;
+        }
         let mut output = ChopOutput::new(output);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        let res = self.panic.call(&mut self.messages, || {
+            let res = self.inner.try_execute(&mut output, &input);
+            if res.is_err() && self.inner.error_output() == ErrorOutput::Empty {
+                output.clear();
+            }
+            res
+        });
+        if let Some(Err(err)) = res {
+            self.messages.report(err);
         }
-        self.inner.execute(&mut output, &input);
+        self.panic
+            .end_cook(&mut self.messages, || self.inner.params_mut());
     }
 
     fn getNumInfoCHOPChans(&mut self) -> i32 {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getNumInfoCHOPChans").entered() };
-        if let Some(info_chop) = self.inner.info_chop() {
-            info_chop.size() as i32
-        } else {
-            0
+        if self.panic.is_poisoned() {
+            return 0;
         }
+        self.panic
+            .call(&mut self.messages, || {
+                if let Some(info_chop) = self.inner.info_chop() {
+                    info_chop.size() as i32
+                } else {
+                    0
+                }
+            })
+            .unwrap_or(0)
     }
 
     fn getInfoCHOPChan(&mut self, index: i32, name: Pin<&mut OP_String>, mut value: Pin<&mut f32>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoCHOPChan").entered() };
-        if let Some(info_chop) = self.inner.info_chop() {
-            let (info_name, info_value) = info_chop.channel(index as usize);
-            unsafe {
-                let new_string = CString::new(info_name.as_str()).unwrap();
-                let new_string_ptr = new_string.as_ptr();
-                name.setString(new_string_ptr);
-            }
-            value.set(info_value);
+        if self.panic.is_poisoned() {
+            return;
         }
+        self.panic.call(&mut self.messages, || {
+            if let Some(info_chop) = self.inner.info_chop() {
+                let (info_name, info_value) = info_chop.channel(index as usize);
+                unsafe {
+                    let new_string = CString::new(info_name.as_str()).unwrap();
+                    let new_string_ptr = new_string.as_ptr();
+                    name.setString(new_string_ptr);
+                }
+                value.set(info_value);
+            }
+        });
     }
 
     fn getInfoDATSize(&mut self, mut info: Pin<&mut OP_InfoDATSize>) -> bool {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoDATSize").entered() };
-        if let Some(info_dat) = self.inner.info_dat() {
-            let (rows, cols) = info_dat.size();
+        if self.panic.is_poisoned() {
+            return false;
+        }
+        let size = self
+            .panic
+            .call(&mut self.messages, || {
+                self.inner.info_dat().map(|info_dat| info_dat.size())
+            })
+            .flatten();
+        if let Some((rows, cols)) = size {
             info.rows = rows as i32;
             info.cols = cols as i32;
             true
@@ -183,22 +269,27 @@ impl RustChopPlugin_methods for RustChopPluginImpl {
     fn getInfoDATEntry(&mut self, index: i32, entryIndex: i32, entry: Pin<&mut OP_String>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoDATEntry").entered() };
-        if let Some(info_dat) = self.inner.info_dat() {
-            let entry_str = info_dat.entry(index as usize, entryIndex as usize);
-            if entry_str.is_empty() {
-                return;
-            }
-            unsafe {
-                let new_string = CString::new(entry_str.as_str()).unwrap();
-                let new_string_ptr = new_string.as_ptr();
-                entry.setString(new_string_ptr);
-            }
+        if self.panic.is_poisoned() {
+            return;
         }
+        self.panic.call(&mut self.messages, || {
+            if let Some(info_dat) = self.inner.info_dat() {
+                let entry_str = info_dat.entry(index as usize, entryIndex as usize);
+                if entry_str.is_empty() {
+                    return;
+                }
+                unsafe {
+                    let new_string = CString::new(entry_str.as_str()).unwrap();
+                    let new_string_ptr = new_string.as_ptr();
+                    entry.setString(new_string_ptr);
+                }
+            }
+        });
     }
 
     fn getWarningString(&mut self, warning: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.warning()).unwrap();
+            let new_string = CString::new(self.messages.warning()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             warning.setString(new_string_ptr);
         }
@@ -206,7 +297,7 @@ impl RustChopPlugin_methods for RustChopPluginImpl {
 
     fn getErrorString(&mut self, error: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.error()).unwrap();
+            let new_string = CString::new(self.messages.error()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             error.setString(new_string_ptr);
         }
@@ -214,7 +305,7 @@ impl RustChopPlugin_methods for RustChopPluginImpl {
 
     fn getInfoPopupString(&mut self, info: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.info()).unwrap();
+            let new_string = CString::new(self.messages.info()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             info.setString(new_string_ptr);
         }
@@ -223,17 +314,27 @@ impl RustChopPlugin_methods for RustChopPluginImpl {
     fn setupParameters(&mut self, manager: Pin<&mut OP_ParameterManager>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("setupParameters").entered() };
-        let params = self.inner.params_mut();
-        if let Some(params) = params {
-            let mut manager = ParameterManager::new(manager);
-            params.register(&mut manager);
-        }
+        self.panic.call(&mut self.messages, || {
+            let params = self.inner.params_mut();
+            if let Some(params) = params {
+                let mut manager = ParameterManager::new(manager);
+                params.register(&mut manager);
+            }
+        });
     }
 
     unsafe fn pulsePressed(&mut self, name: *const std::ffi::c_char) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("pulsePressed").entered() };
-        self.inner
-            .pulse_pressed(std::ffi::CStr::from_ptr(name).to_str().unwrap());
+        // A pulse counts as a parameter change, so give a poisoned
+        // instance another chance to cook.
+        if self.panic.is_poisoned() {
+            self.panic.reset(&mut self.messages);
+        }
+        self.panic.call(&mut self.messages, || {
+            let name = std::ffi::CStr::from_ptr(name).to_str().unwrap();
+            self.inner.dispatch_pulse(name);
+            self.inner.pulse_pressed(name);
+        });
     }
 }
@@ -69,6 +69,13 @@ impl<'execute> ChopOutput<'execute> {
         self.output.startIndex as usize
     }
 
+    /// Set every sample in the output buffer to zero.
+    pub fn clear(&mut self) {
+        for i in 0..self.num_channels() {
+            self.channel_mut(i).fill(0.0);
+        }
+    }
+
     pub fn channel(&self, index: usize) -> &[f32] {
         if index >= self.num_channels() {
             panic!("Channel index out of bounds");
@@ -112,8 +119,28 @@ pub trait Chop: Op {
         String::from("")
     }
 
+    /// Cook the operator, called by the default [`Chop::try_execute`]. A
+    /// chop which overrides that instead can forward this to it.
     fn execute(&mut self, output: &mut ChopOutput, input: &OperatorInputs<ChopInput>);
 
+    /// Fallible version of [`Chop::execute`], which is what the framework
+    /// calls. An error is reported on the node and the output is handled
+    /// according to [`Chop::error_output`].
+    fn try_execute(
+        &mut self,
+        output: &mut ChopOutput,
+        input: &OperatorInputs<ChopInput>,
+    ) -> Result<(), OpError> {
+        self.execute(output, input);
+        Ok(())
+    }
+
+    /// What to do with the output when [`Chop::try_execute`] fails.
+    /// [`ErrorOutput::Empty`] zeroes every channel.
+    fn error_output(&self) -> ErrorOutput {
+        ErrorOutput::Unchanged
+    }
+
     fn general_info(&self, input: &OperatorInputs<ChopInput>) -> ChopGeneralInfo;
 
     fn output_info(&self, _input: &OperatorInputs<ChopInput>) -> Option<ChopOutputInfo> {
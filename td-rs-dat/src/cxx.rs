@@ -8,7 +8,9 @@ use autocxx::subclass::*;
 use std::ffi::CString;
 
 use std::pin::Pin;
-use td_rs_base::{param::ParameterManager, NodeInfo, OperatorInputs};
+use td_rs_base::{
+    param::ParameterManager, ErrorOutput, NodeInfo, Op, OpMessages, OperatorInputs, PanicGuard,
+};
 
 include_cpp! {
     #include "DAT_CPlusPlusBase.h"
@@ -60,20 +62,37 @@ impl AsPlugin for RustDatPluginImplCpp {
 #[subclass(superclass("RustDatPlugin"))]
 pub struct RustDatPluginImpl {
     inner: Box<dyn Dat>,
+    messages: OpMessages,
+    panic: PanicGuard,
 }
 
 #[no_mangle]
 extern "C" fn dat_new(info: &'static OP_NodeInfo) -> *mut RustDatPluginImplCpp {
-    unsafe {
-        let info = NodeInfo::new(info);
-        RustDatPluginImpl::new_cpp_owned(RustDatPluginImpl {
-            inner: dat_new_impl(info),
-            cpp_peer: CppSubclassCppPeerHolder::Empty,
-        })
-        .into_raw()
-    }
+    let info = NodeInfo::new(info);
+    let mut messages = OpMessages::new();
+    let mut panic = PanicGuard::new();
+    let inner = panic.call(&mut messages, || unsafe { dat_new_impl(info) });
+    let inner = inner.unwrap_or_else(|| {
+        panic.set_fatal();
+        Box::new(FailedDat)
+    });
+    RustDatPluginImpl::new_cpp_owned(RustDatPluginImpl {
+        inner,
+        messages,
+        panic,
+        cpp_peer: CppSubclassCppPeerHolder::Empty,
+    })
+    .into_raw()
 }
 
+/// Stands in for a plugin whose constructor panicked, so the node is still
+/// created and shows the panic as its error.
+struct FailedDat;
+
+impl Op for FailedDat {}
+
+impl Dat for FailedDat {}
+
 impl RustDatPlugin_methods for RustDatPluginImpl {
     fn inner(&self) -> *mut c_void {
         self.inner.as_ref() as *const dyn Dat as *mut c_void
@@ -86,57 +105,104 @@ impl RustDatPlugin_methods for RustDatPluginImpl {
     fn getGeneralInfo(&mut self, mut info: Pin<&mut DAT_GeneralInfo>, inputs: &OP_Inputs) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getGeneralInfo").entered() };
+        if !self.panic.is_poisoned() {
+            self.messages.clear();
+        }
         let input = OperatorInputs::new(inputs);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return;
+        }
+        let gen_info = self
+            .panic
+            .call(&mut self.messages, || self.inner.general_info(&input));
+        if let Some(gen_info) = gen_info {
+            info.cookEveryFrame = gen_info.cook_every_frame;
+            info.cookEveryFrameIfAsked = gen_info.cook_every_frame_if_asked;
         }
-        let gen_info = self.inner.general_info(&input);
-        info.cookEveryFrame = gen_info.cook_every_frame;
-        info.cookEveryFrameIfAsked = gen_info.cook_every_frame_if_asked;
     }
 
-    fn execute(&mut self, outputs: Pin<&mut DAT_Output>, inputs: &OP_Inputs) {
+    fn execute(&mut self, mut outputs: Pin<&mut DAT_Output>, inputs: &OP_Inputs) {
         #[cfg(feature = "tracing")]
-        let span = {
-            tracing_base::trace_span!("execute").entered();
-        };
+        let _span = { tracing_base::trace_span!("execute").entered() };
         let input = OperatorInputs::new(inputs);
-        let output = DatOutput::new(outputs);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return;
+        }
+        let res = self.panic.call(&mut self.messages, || {
+            let res = self
+                .inner
+                .try_execute(DatOutput::new(outputs.as_mut()), &input);
+            if res.is_err() && self.inner.error_output() == ErrorOutput::Empty {
+                DatOutput::new(outputs).clear();
+            }
+            res
+        });
+        if let Some(Err(err)) = res {
+            self.messages.report(err);
         }
-        self.inner.execute(output, &input);
+        self.panic
+            .end_cook(&mut self.messages, || self.inner.params_mut());
     }
 
     fn getNumInfoCHOPChans(&mut self) -> i32 {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getNumInfoCHOPChans").entered() };
-        if let Some(info_chop) = self.inner.info_chop() {
-            info_chop.size() as i32
-        } else {
-            0
+        if self.panic.is_poisoned() {
+            return 0;
         }
+        self.panic
+            .call(&mut self.messages, || {
+                if let Some(info_chop) = self.inner.info_chop() {
+                    info_chop.size() as i32
+                } else {
+                    0
+                }
+            })
+            .unwrap_or(0)
     }
 
     fn getInfoCHOPChan(&mut self, index: i32, name: Pin<&mut OP_String>, mut value: Pin<&mut f32>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoCHOPChan").entered() };
-        if let Some(info_chop) = self.inner.info_chop() {
-            let (info_name, info_value) = info_chop.channel(index as usize);
-            unsafe {
-                let new_string = CString::new(info_name.as_str()).unwrap();
-                let new_string_ptr = new_string.as_ptr();
-                name.setString(new_string_ptr);
-            }
-            value.set(info_value);
+        if self.panic.is_poisoned() {
+            return;
         }
+        self.panic.call(&mut self.messages, || {
+            if let Some(info_chop) = self.inner.info_chop() {
+                let (info_name, info_value) = info_chop.channel(index as usize);
+                unsafe {
+                    let new_string = CString::new(info_name.as_str()).unwrap();
+                    let new_string_ptr = new_string.as_ptr();
+                    name.setString(new_string_ptr);
+                }
+                value.set(info_value);
+            }
+        });
     }
 
     fn getInfoDATSize(&mut self, mut info: Pin<&mut OP_InfoDATSize>) -> bool {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoDATSize").entered() };
-        if let Some(info_dat) = self.inner.info_dat() {
-            let (rows, cols) = info_dat.size();
+        if self.panic.is_poisoned() {
+            return false;
+        }
+        let size = self
+            .panic
+            .call(&mut self.messages, || {
+                self.inner.info_dat().map(|info_dat| info_dat.size())
+            })
+            .flatten();
+        if let Some((rows, cols)) = size {
             info.rows = rows as i32;
             info.cols = cols as i32;
             true
@@ -148,22 +214,27 @@ impl RustDatPlugin_methods for RustDatPluginImpl {
     fn getInfoDATEntry(&mut self, index: i32, entryIndex: i32, entry: Pin<&mut OP_String>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoDATEntry").entered() };
-        if let Some(info_dat) = self.inner.info_dat() {
-            let entry_str = info_dat.entry(index as usize, entryIndex as usize);
-            if entry_str.is_empty() {
-                return;
-            }
-            unsafe {
-                let new_string = CString::new(entry_str.as_str()).unwrap();
-                let new_string_ptr = new_string.as_ptr();
-                entry.setString(new_string_ptr);
-            }
+        if self.panic.is_poisoned() {
+            return;
         }
+        self.panic.call(&mut self.messages, || {
+            if let Some(info_dat) = self.inner.info_dat() {
+                let entry_str = info_dat.entry(index as usize, entryIndex as usize);
+                if entry_str.is_empty() {
+                    return;
+                }
+                unsafe {
+                    let new_string = CString::new(entry_str.as_str()).unwrap();
+                    let new_string_ptr = new_string.as_ptr();
+                    entry.setString(new_string_ptr);
+                }
+            }
+        });
     }
 
     fn getWarningString(&mut self, warning: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.warning()).unwrap();
+            let new_string = CString::new(self.messages.warning()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             warning.setString(new_string_ptr);
         }
@@ -171,7 +242,7 @@ impl RustDatPlugin_methods for RustDatPluginImpl {
 
     fn getErrorString(&mut self, error: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.error()).unwrap();
+            let new_string = CString::new(self.messages.error()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             error.setString(new_string_ptr);
         }
@@ -179,7 +250,7 @@ impl RustDatPlugin_methods for RustDatPluginImpl {
 
     fn getInfoPopupString(&mut self, info: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.info()).unwrap();
+            let new_string = CString::new(self.messages.info()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             info.setString(new_string_ptr);
         }
@@ -188,17 +259,27 @@ impl RustDatPlugin_methods for RustDatPluginImpl {
     fn setupParameters(&mut self, manager: Pin<&mut OP_ParameterManager>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("setupParameters").entered() };
-        let params = self.inner.params_mut();
-        if let Some(params) = params {
-            let mut manager = ParameterManager::new(manager);
-            params.register(&mut manager);
-        }
+        self.panic.call(&mut self.messages, || {
+            let params = self.inner.params_mut();
+            if let Some(params) = params {
+                let mut manager = ParameterManager::new(manager);
+                params.register(&mut manager);
+            }
+        });
     }
 
     unsafe fn pulsePressed(&mut self, name: *const std::ffi::c_char) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("pulsePressed").entered() };
-        self.inner
-            .pulse_pressed(std::ffi::CStr::from_ptr(name).to_str().unwrap());
+        // A pulse counts as a parameter change, so give a poisoned
+        // instance another chance to cook.
+        if self.panic.is_poisoned() {
+            self.panic.reset(&mut self.messages);
+        }
+        self.panic.call(&mut self.messages, || {
+            let name = std::ffi::CStr::from_ptr(name).to_str().unwrap();
+            self.inner.dispatch_pulse(name);
+            self.inner.pulse_pressed(name);
+        });
     }
 }
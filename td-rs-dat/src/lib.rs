@@ -38,6 +38,14 @@ impl<'execute> DatOutput<'execute> {
         table_out
     }
 
+    /// Output an empty table.
+    pub fn clear(mut self) {
+        self.output
+            .as_mut()
+            .setOutputDataType(cxx::DAT_OutDataType::Table);
+        self.output.as_mut().setTableSize(0, 0);
+    }
+
     pub fn text(mut self) -> DatTextOutput<'execute> {
         self.output
             .as_mut()
@@ -236,6 +244,24 @@ pub trait Dat: Op {
     fn execute(&mut self, _output: DatOutput, _input: &OperatorInputs<DatInput>) {
         // Do nothing by default.
     }
+
+    /// Fallible version of [`Dat::execute`], which is what the framework
+    /// calls. An error is reported on the node and the output is handled
+    /// according to [`Dat::error_output`].
+    fn try_execute(
+        &mut self,
+        output: DatOutput,
+        input: &OperatorInputs<DatInput>,
+    ) -> Result<(), OpError> {
+        self.execute(output, input);
+        Ok(())
+    }
+
+    /// What to do with the output when [`Dat::try_execute`] fails.
+    /// [`ErrorOutput::Empty`] outputs an empty table.
+    fn error_output(&self) -> ErrorOutput {
+        ErrorOutput::Unchanged
+    }
 }
 
 #[macro_export]
@@ -0,0 +1,385 @@
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, ExprLit, ExprPath, ExprUnary, Ident, Lit, Path, Token, UnOp};
+
+/// A single `name` or `name = value` argument of a `#[param(...)]` attribute.
+struct ParamArg {
+    name: Ident,
+    value: Option<Expr>,
+}
+
+impl Parse for ParamArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = Ident::parse_any(input)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { name, value })
+    }
+}
+
+/// The options given to a field by its `#[param(...)]` attributes.
+#[derive(Default)]
+pub struct FieldAttrs {
+    /// The name of the parameter, in place of one made from the field name.
+    pub name: Option<String>,
+    pub label: Option<String>,
+    pub page: Option<String>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub min_slider: Option<f64>,
+    pub max_slider: Option<f64>,
+    pub clamp: Option<bool>,
+    pub default: Option<Expr>,
+    /// The `ParamStyle` variant of the widget to use.
+    pub style: Option<Ident>,
+    /// Register the field's own parameters in place of the field.
+    pub flatten: bool,
+    /// A prefix added to the names of a flattened field's parameters.
+    pub prefix: Option<String>,
+    /// A condition under which the parameter is enabled.
+    pub enable_if: Option<Expr>,
+    /// A method run when the field changes.
+    pub on_change: Option<Ident>,
+    /// A method of the operator run when the pulse is pressed.
+    pub on_pulse: Option<Ident>,
+    /// A method returning the entries of a string menu.
+    pub entries: Option<Ident>,
+    /// The parameter's tooltip.
+    pub help: Option<String>,
+    /// Start a new section on the page at the parameter.
+    pub section: bool,
+    /// Disable the parameter so it can't be edited.
+    pub readonly: bool,
+    /// Keep the field out of TouchDesigner, so it is only saved in presets.
+    pub hidden: bool,
+    /// Register the parameters of each element of a `Vec` field.
+    pub count: Option<Count>,
+    /// The number of elements registered for a count parameter.
+    pub max_count: Option<usize>,
+}
+
+/// The number of elements of a sequence field.
+pub enum Count {
+    /// A fixed number of elements.
+    Fixed(usize),
+    /// An integer field giving the number of elements.
+    Field(Ident),
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut field_attrs = FieldAttrs::default();
+        let mut names = Vec::new();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("param")) {
+            let args = attr.parse_args_with(Punctuated::<ParamArg, Token![,]>::parse_terminated)?;
+            for arg in args {
+                names.push(arg.name.clone());
+                field_attrs.apply(arg)?;
+            }
+        }
+        field_attrs.check(&names)?;
+        Ok(field_attrs)
+    }
+
+    // A flattened or sequence field only takes the options which apply to
+    // the whole group.
+    fn check(&self, names: &[Ident]) -> syn::Result<()> {
+        for name in names {
+            if self.flatten
+                && !["flatten", "prefix", "page", "on_change"]
+                    .iter()
+                    .any(|n| name == n)
+            {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{}` cannot be used with `flatten`", name),
+                ));
+            }
+            if !self.flatten
+                && self.count.is_some()
+                && !["count", "max_count", "prefix", "page", "on_change"]
+                    .iter()
+                    .any(|n| name == n)
+            {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{}` cannot be used with `count`", name),
+                ));
+            }
+            if !self.flatten && self.count.is_none() && name == "prefix" {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`prefix` can only be used with `flatten` or `count`",
+                ));
+            }
+            if name == "max_count" && !matches!(self.count, Some(Count::Field(_))) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`max_count` can only be used when `count` names a field",
+                ));
+            }
+            // Parameters can only be registered once, so a count field can
+            // only choose how many of them are used.
+            if name == "count"
+                && matches!(self.count, Some(Count::Field(_)))
+                && self.max_count.is_none()
+            {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`count = \"field\"` requires `max_count`",
+                ));
+            }
+            // Both of these set whether the parameter is enabled.
+            if name == "enable_if" && (self.readonly || self.hidden) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`enable_if` cannot be used with `readonly` or `hidden`",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, arg: ParamArg) -> syn::Result<()> {
+        let name = arg.name.to_string();
+        let span = arg.name.span();
+        let value = || {
+            arg.value
+                .as_ref()
+                .ok_or_else(|| syn::Error::new(span, format!("`{}` requires a value", name)))
+        };
+        match name.as_str() {
+            "name" => self.name = Some(param_name(value()?)?),
+            "label" => self.label = Some(string(value()?)?),
+            "page" => self.page = Some(string(value()?)?),
+            "min" => self.min = Some(number(value()?)?),
+            "max" => self.max = Some(number(value()?)?),
+            "min_slider" => self.min_slider = Some(number(value()?)?),
+            "max_slider" => self.max_slider = Some(number(value()?)?),
+            "clamp" => self.clamp = Some(flag(arg.value.as_ref())?),
+            "default" => self.default = Some(value()?.clone()),
+            "style" => self.style = Some(style(value()?)?),
+            "flatten" => self.flatten = flag(arg.value.as_ref())?,
+            "prefix" => self.prefix = Some(param_name(value()?)?),
+            "enable_if" => self.enable_if = Some(condition(value()?)?),
+            "on_change" => self.on_change = Some(method(value()?)?),
+            "on_pulse" => self.on_pulse = Some(method(value()?)?),
+            "entries" => self.entries = Some(method(value()?)?),
+            "help" => self.help = Some(string(value()?)?),
+            "section" => self.section = flag(arg.value.as_ref())?,
+            "readonly" => self.readonly = flag(arg.value.as_ref())?,
+            "hidden" => self.hidden = flag(arg.value.as_ref())?,
+            "count" => self.count = Some(count(value()?)?),
+            "max_count" => self.max_count = Some(int(value()?)?),
+            _ => {
+                return Err(syn::Error::new(
+                    span,
+                    format!("unknown parameter attribute `{}`", name),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The options given to a struct by its `#[params(...)]` attributes.
+#[derive(Default)]
+pub struct StructAttrs {
+    /// The operator whose methods are run by `on_pulse`.
+    pub op: Option<Path>,
+    /// Whether to implement `Serialize` and `Deserialize` by parameter name.
+    pub serde: bool,
+}
+
+impl StructAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut struct_attrs = StructAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("params")) {
+            let args = attr.parse_args_with(Punctuated::<ParamArg, Token![,]>::parse_terminated)?;
+            for arg in args {
+                let name = arg.name.to_string();
+                match (name.as_str(), &arg.value) {
+                    ("op", Some(Expr::Path(ExprPath { path, .. }))) => {
+                        struct_attrs.op = Some(path.clone())
+                    }
+                    ("op", _) => {
+                        return Err(syn::Error::new(
+                            arg.name.span(),
+                            "`op` requires the operator's type",
+                        ))
+                    }
+                    ("serde", value) => struct_attrs.serde = flag(value.as_ref())?,
+                    _ => {
+                        return Err(syn::Error::new(
+                            arg.name.span(),
+                            format!("unknown params attribute `{}`", name),
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(struct_attrs)
+    }
+}
+
+/// Whether `name` is allowed by TouchDesigner: an uppercase letter followed by
+/// lowercase letters and digits.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map_or(false, |c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+}
+
+fn param_name(expr: &Expr) -> syn::Result<String> {
+    let name = string(expr)?;
+    if !is_valid_name(&name) {
+        return Err(syn::Error::new(
+            expr.span(),
+            format!(
+                "`{}` is not a valid parameter name, expected an uppercase letter followed by lowercase letters and digits",
+                name
+            ),
+        ));
+    }
+    Ok(name)
+}
+
+fn string(expr: &Expr) -> syn::Result<String> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => Ok(lit.value()),
+        _ => Err(syn::Error::new(expr.span(), "expected a string literal")),
+    }
+}
+
+// Conditions are written as strings so they can use `==` and `&&`.
+fn condition(expr: &Expr) -> syn::Result<Expr> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => lit.parse(),
+        _ => Err(syn::Error::new(expr.span(), "expected a string literal")),
+    }
+}
+
+fn method(expr: &Expr) -> syn::Result<Ident> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) => lit.parse(),
+        _ => Err(syn::Error::new(expr.span(), "expected a string literal")),
+    }
+}
+
+fn count(expr: &Expr) -> syn::Result<Count> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(_), ..
+        }) => method(expr).map(Count::Field),
+        _ => int(expr).map(Count::Fixed),
+    }
+}
+
+fn int(expr: &Expr) -> syn::Result<usize> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        _ => Err(syn::Error::new(expr.span(), "expected an integer literal")),
+    }
+}
+
+fn style(expr: &Expr) -> syn::Result<Ident> {
+    let variant = match string(expr)?.as_str() {
+        "xy" => "Xy",
+        "xyz" => "Xyz",
+        "uv" => "Uv",
+        "uvw" => "Uvw",
+        "wh" => "Wh",
+        "rgb" => "Rgb",
+        "rgba" => "Rgba",
+        _ => {
+            return Err(syn::Error::new(
+                expr.span(),
+                "expected one of \"xy\", \"xyz\", \"uv\", \"uvw\", \"wh\", \"rgb\" or \"rgba\"",
+            ))
+        }
+    };
+    Ok(Ident::new(variant, expr.span()))
+}
+
+/// Parse a float or integer literal, which may be negated, or the `MIN` or
+/// `MAX` of an integer type such as `i32::MAX`.
+pub fn number(expr: &Expr) -> syn::Result<f64> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(lit),
+            ..
+        }) => lit.base10_parse(),
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse::<f64>(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => number(expr).map(|n| -n),
+        Expr::Path(ExprPath { path, .. }) => {
+            int_bound(path).ok_or_else(|| syn::Error::new(expr.span(), "expected a number"))
+        }
+        _ => Err(syn::Error::new(expr.span(), "expected a number")),
+    }
+}
+
+fn int_bound(path: &Path) -> Option<f64> {
+    let segments = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>();
+    let (min, max) = match segments.first()?.as_str() {
+        "i8" => (i8::MIN as f64, i8::MAX as f64),
+        "i16" => (i16::MIN as f64, i16::MAX as f64),
+        "i32" => (i32::MIN as f64, i32::MAX as f64),
+        "i64" => (i64::MIN as f64, i64::MAX as f64),
+        "i128" => (i128::MIN as f64, i128::MAX as f64),
+        "u8" => (0.0, u8::MAX as f64),
+        "u16" => (0.0, u16::MAX as f64),
+        "u32" => (0.0, u32::MAX as f64),
+        "u64" => (0.0, u64::MAX as f64),
+        "u128" => (0.0, u128::MAX as f64),
+        _ => return None,
+    };
+    match segments.get(1..)? {
+        [bound] if bound == "MIN" => Some(min),
+        [bound] if bound == "MAX" => Some(max),
+        _ => None,
+    }
+}
+
+// A bare flag such as `clamp` is true.
+fn flag(expr: Option<&Expr>) -> syn::Result<bool> {
+    match expr {
+        None => Ok(true),
+        Some(Expr::Lit(ExprLit {
+            lit: Lit::Bool(lit),
+            ..
+        })) => Ok(lit.value),
+        Some(expr) => Err(syn::Error::new(expr.span(), "expected `true` or `false`")),
+    }
+}
+
+/// Combine errors so they are all reported at once.
+pub fn combine(errors: Vec<syn::Error>) -> Option<syn::Error> {
+    errors.into_iter().reduce(|mut a, b| {
+        a.combine(b);
+        a
+    })
+}
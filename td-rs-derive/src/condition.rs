@@ -0,0 +1,94 @@
+use proc_macro2::TokenStream;
+use quote::quote_spanned;
+use syn::spanned::Spanned;
+use syn::{BinOp, Expr, ExprBinary, ExprCall, ExprParen, ExprPath, ExprUnary, Ident, Type, UnOp};
+
+/// Expand an `enable_if` condition to a `bool` expression evaluated after the
+/// fields of `self` have been updated from `inputs`.
+///
+/// Conditions are made of:
+/// - `field`, a `bool` field,
+/// - `field == Variant` or `field != Variant`, a menu field's variant,
+/// - `input(n)`, whether input `n` is connected,
+///
+/// combined with `!`, `&&`, `||` and parentheses.
+pub fn expand(expr: &Expr, fields: &[(&Ident, &Type)]) -> syn::Result<TokenStream> {
+    let span = expr.span();
+    match expr {
+        Expr::Path(path) => {
+            let field = field(path, fields)?.0;
+            Ok(quote_spanned!(span=> self.#field))
+        }
+        Expr::Paren(ExprParen { expr, .. }) => {
+            let expr = expand(expr, fields)?;
+            Ok(quote_spanned!(span=> (#expr)))
+        }
+        Expr::Unary(ExprUnary {
+            op: UnOp::Not(_),
+            expr,
+            ..
+        }) => {
+            let expr = expand(expr, fields)?;
+            Ok(quote_spanned!(span=> !#expr))
+        }
+        Expr::Binary(ExprBinary {
+            left, op, right, ..
+        }) => match op {
+            BinOp::And(_) | BinOp::Or(_) => {
+                let left = expand(left, fields)?;
+                let right = expand(right, fields)?;
+                Ok(quote_spanned!(span=> #left #op #right))
+            }
+            BinOp::Eq(_) | BinOp::Ne(_) => {
+                let (field, ty) = match &**left {
+                    Expr::Path(path) => field(path, fields)?,
+                    _ => return Err(syn::Error::new(left.span(), "expected a field")),
+                };
+                let variant = match &**right {
+                    Expr::Path(ExprPath { path, .. }) => match path.get_ident() {
+                        Some(variant) => quote_spanned!(variant.span()=> <#ty>::#variant),
+                        None => quote_spanned!(path.span()=> #path),
+                    },
+                    _ => return Err(syn::Error::new(right.span(), "expected a variant")),
+                };
+                let matches = quote_spanned!(span=> matches!(self.#field, #variant));
+                Ok(match op {
+                    BinOp::Eq(_) => matches,
+                    _ => quote_spanned!(span=> !#matches),
+                })
+            }
+            _ => Err(unsupported(span)),
+        },
+        Expr::Call(ExprCall { func, args, .. }) => match &**func {
+            Expr::Path(ExprPath { path, .. }) if path.is_ident("input") && args.len() == 1 => {
+                let index = &args[0];
+                Ok(quote_spanned!(span=> inputs.input_connected(#index)))
+            }
+            _ => Err(unsupported(span)),
+        },
+        _ => Err(unsupported(span)),
+    }
+}
+
+// The field named by `path`, keeping the span of the condition.
+fn field<'a, 'b>(
+    path: &'a ExprPath,
+    fields: &[(&Ident, &'b Type)],
+) -> syn::Result<(&'a Ident, &'b Type)> {
+    let ident = path
+        .path
+        .get_ident()
+        .ok_or_else(|| syn::Error::new(path.span(), "expected a field"))?;
+    fields
+        .iter()
+        .find(|(name, _)| *name == ident)
+        .map(|(_, ty)| (ident, *ty))
+        .ok_or_else(|| syn::Error::new(ident.span(), format!("no field `{}`", ident)))
+}
+
+fn unsupported(span: proc_macro2::Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "unsupported condition, expected a field, `field == Variant` or `input(n)`",
+    )
+}
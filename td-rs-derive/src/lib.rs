@@ -1,17 +1,23 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use std::collections::HashMap;
 
-use quote::quote;
+use quote::{format_ident, quote, quote_spanned};
 
-use syn::{
-    parse_macro_input, Data, DeriveInput, Fields, Lit, Meta, MetaNameValue, NestedMeta, Variant,
-};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, ExprLit, Fields, Ident, Lit, Type, Variant};
+
+use attr::{Count, FieldAttrs, StructAttrs};
+
+mod attr;
+mod condition;
 
 #[proc_macro_derive(Param)]
 pub fn derive_param(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
+    let base = base_path();
     let enum_ident = input.ident;
     let enum_data = if let Data::Enum(data) = input.data {
         data
@@ -31,10 +37,14 @@ pub fn derive_param(input: TokenStream) -> TokenStream {
         .collect::<Vec<String>>();
 
     let variants = enum_data.variants.into_iter().collect::<Vec<Variant>>();
+    let variant_idents = variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
     let try_from_i32_match_arms = try_from_i32_match_arms(&variants);
 
     let output = quote! {
-        impl MenuParam for #enum_ident {
+        impl #base::MenuParam for #enum_ident {
             fn names() -> Vec<String> {
                 vec![
                     #(String::from(#variant_names)),*
@@ -59,19 +69,51 @@ pub fn derive_param(input: TokenStream) -> TokenStream {
             }
         }
 
-        impl Param for #enum_ident {
-            fn register(&self, options: ParamOptions, parameter_manager: &mut ParameterManager) {
-                let param: StringParameter = options.into();
-                let names = #enum_ident::names();
-                let labels = #enum_ident::labels();
-                parameter_manager.append_menu(param, &names, &labels);
+        impl #base::Param for #enum_ident {
+            fn descriptor(&self, options: &#base::ParamOptions) -> #base::ParameterDescriptor {
+                let mut descriptor = <Self as #base::Param>::describe(options);
+                if let #base::ParameterDescriptor::Menu { default, .. } = &mut descriptor {
+                    *default = match self {
+                        #(Self::#variant_idents => #variant_names.to_string(),)*
+                    };
+                }
+                descriptor
             }
 
-            fn update(&mut self, name: &str, inputs: &ParamInputs) {
+            fn describe(_options: &#base::ParamOptions) -> #base::ParameterDescriptor {
+                let entries = <#enum_ident as #base::MenuParam>::names()
+                    .into_iter()
+                    .zip(<#enum_ident as #base::MenuParam>::labels())
+                    .map(|(name, label)| #base::MenuEntry { name, label })
+                    .collect();
+                #base::ParameterDescriptor::Menu {
+                    default: String::new(),
+                    entries,
+                }
+            }
+
+            fn update(&mut self, name: &str, inputs: &#base::ParamInputs) {
                 let idx = inputs.get_int(name, 0);
                 let value = #enum_ident::try_from(idx).unwrap();
                 *self = value;
             }
+
+            fn preset_value(&self) -> Option<#base::PresetValue> {
+                let name = match self {
+                    #(Self::#variant_idents => #variant_names,)*
+                };
+                Some(#base::PresetValue::String(name.to_string()))
+            }
+
+            fn apply_preset_value(&mut self, name: &str, value: &#base::PresetValue) -> Result<(), #base::PresetError> {
+                match value {
+                    #(#base::PresetValue::String(variant) if variant == #variant_names => {
+                        *self = Self::#variant_idents;
+                        Ok(())
+                    })*
+                    _ => Err(#base::PresetError::wrong_type(name, "one of the menu entries", value)),
+                }
+            }
         }
     };
 
@@ -105,141 +147,786 @@ fn camel_case_to_words(s: &str) -> String {
     words
 }
 
-#[proc_macro_derive(Params, attributes(param))]
+#[proc_macro_derive(Params, attributes(param, params))]
 pub fn params_derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     impl_params(&input)
 }
 
 fn impl_params(input: &DeriveInput) -> TokenStream {
+    let base = base_path();
     let struct_name = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     let mut register_code = Vec::new();
     let mut update_code = Vec::new(); // Add this line to store update code
+    let mut describe_code = Vec::new();
+    let mut enable_code = Vec::new();
+    let mut hook_code = Vec::new();
+    let mut changed_fields = Vec::new();
+    let mut changes_field = None;
+    let mut pulse_code = Vec::new();
+    let mut end_cook_code = Vec::new();
+    let mut preset_code = Vec::new();
+    let mut apply_preset_code = Vec::new();
+    // Sequences are updated last, so they see the new value of a count field.
+    let mut sequence_update_code = Vec::new();
+    let mut sequence_apply_code = Vec::new();
+    let mut param_names = Vec::new();
+    let mut style_checks = Vec::new();
+    let mut names = HashMap::new();
+    let mut errors = Vec::new();
+
+    let struct_attrs = StructAttrs::parse(&input.attrs).unwrap_or_else(|err| {
+        errors.push(err);
+        StructAttrs::default()
+    });
 
     if let Data::Struct(data_struct) = &input.data {
         if let Fields::Named(named_fields) = &data_struct.fields {
+            let fields = named_fields
+                .named
+                .iter()
+                .map(|field| (field.ident.as_ref().unwrap(), &field.ty))
+                .collect::<Vec<_>>();
+
+            // A `ParamChanges` field records which of the other fields changed.
+            for (name, ty) in &fields {
+                if is_type(ty, &["ParamChanges"]) {
+                    if changes_field.is_some() {
+                        errors.push(syn::Error::new(
+                            name.span(),
+                            "only one `ParamChanges` field is allowed",
+                        ));
+                    }
+                    changes_field = Some(*name);
+                }
+            }
+
             for field in named_fields.named.iter() {
                 let field_name = field.ident.as_ref().unwrap();
-                let _field_type = &field.ty;
-
-                let mut label = None;
-                let mut page = None;
-                let mut min = None;
-                let mut max = None;
-                let mut min_slider = None;
-                let mut max_slider = None;
-                let mut clamp = None;
-                let mut default = None;
-
-                for attr in &field.attrs {
-                    if attr.path.is_ident("param") {
-                        if let Ok(Meta::List(meta_list)) = attr.parse_meta() {
-                            for nested_meta in meta_list.nested.iter() {
-                                if let NestedMeta::Meta(Meta::NameValue(MetaNameValue {
-                                    path,
-                                    lit,
-                                    ..
-                                })) = nested_meta
-                                {
-                                    if path.is_ident("label") {
-                                        if let Lit::Str(lit_str) = lit {
-                                            label = Some(lit_str.value());
-                                        }
-                                    } else if path.is_ident("page") {
-                                        if let Lit::Str(lit_str) = lit {
-                                            page = Some(lit_str.value());
-                                        }
-                                    } else if path.is_ident("min") {
-                                        if let Lit::Float(lit_float) = lit {
-                                            min = Some(lit_float.base10_parse().unwrap());
-                                        }
-                                    } else if path.is_ident("max") {
-                                        if let Lit::Float(lit_float) = lit {
-                                            max = Some(lit_float.base10_parse().unwrap());
-                                        }
-                                    } else if path.is_ident("min_slider") {
-                                        if let Lit::Float(lit_float) = lit {
-                                            min_slider = Some(lit_float.base10_parse().unwrap());
-                                        }
-                                    } else if path.is_ident("max_slider") {
-                                        if let Lit::Float(lit_float) = lit {
-                                            max_slider = Some(lit_float.base10_parse().unwrap());
-                                        }
-                                    } else if path.is_ident("clamp") {
-                                        if let Lit::Bool(lit_bool) = lit {
-                                            clamp = Some(lit_bool.value);
-                                        }
-                                    } else if path.is_ident("default") {
-                                        if let Lit::Float(lit_float) = lit {
-                                            default = Some(lit_float.base10_parse().unwrap());
+                let field_type = &field.ty;
+
+                let attrs = match FieldAttrs::parse(&field.attrs) {
+                    Ok(attrs) => attrs,
+                    Err(err) => {
+                        errors.push(err);
+                        continue;
+                    }
+                };
+
+                let field_str = field_name.to_string();
+
+                if changes_field == Some(field_name) {
+                    continue;
+                }
+                let index = changed_fields.len();
+                changed_fields.push(field_name);
+                if let Some(method) = &attrs.on_change {
+                    match changes_field {
+                        Some(changes) => hook_code.push(quote! {
+                            if self.#changes.is_updated(#index) {
+                                self.#method();
+                            }
+                        }),
+                        None => errors.push(syn::Error::new(
+                            method.span(),
+                            "`on_change` requires a `ParamChanges` field",
+                        )),
+                    }
+                }
+
+                if let Some(count) = &attrs.count {
+                    let element_type = match vec_element(field_type) {
+                        Some(element_type) => element_type,
+                        None => {
+                            errors.push(syn::Error::new(
+                                field_type.span(),
+                                "`count` can only be used on `Vec` fields",
+                            ));
+                            continue;
+                        }
+                    };
+                    let prefix = attrs.prefix.clone().unwrap_or_default();
+                    let page = attrs
+                        .page
+                        .clone()
+                        .unwrap_or_else(|| format_name(&field_str));
+                    let (len, max) = match count {
+                        Count::Fixed(len) => (quote! { #len }, *len),
+                        Count::Field(count_field) => {
+                            if !fields.iter().any(|(name, _)| *name == count_field) {
+                                errors.push(syn::Error::new(
+                                    count_field.span(),
+                                    format!("no field `{}`", count_field),
+                                ));
+                            }
+                            let max = attrs.max_count.unwrap_or_default();
+                            (
+                                quote! { usize::try_from(self.#count_field).unwrap_or(0).min(#max) },
+                                max,
+                            )
+                        }
+                    };
+                    param_names.push(quote! {
+                        #base::ParamName::Sequence(#prefix, #max, <#element_type as #base::ParamNames>::PARAM_NAMES)
+                    });
+                    let element_group = quote! { group.element(#prefix, index, Some(#page)) };
+                    register_code.push(quote! {
+                        for index in 0..#max {
+                            let element_group = #element_group;
+                            match self.#field_name.get_mut(index) {
+                                Some(element) => #base::OperatorParams::register_group(element, parameter_manager, element_group),
+                                None => {
+                                    let mut element = <#element_type as Default>::default();
+                                    #base::OperatorParams::register_group(&mut element, parameter_manager, element_group);
+                                }
+                            }
+                        }
+                    });
+                    end_cook_code.push(quote! {
+                        for element in &mut self.#field_name {
+                            #base::OperatorParams::end_cook(element);
+                        }
+                    });
+                    // Every element is registered, and those past the length
+                    // are disabled whenever the length changes.
+                    sequence_update_code.push(track_changes(
+                        changes_field,
+                        index,
+                        quote! {
+                            {
+                                let len = #len;
+                                if inputs.is_first_update() || self.#field_name.len() != len {
+                                    let fields = <#element_type as #base::OperatorParams>::describe();
+                                    for index in 0..#max {
+                                        let element_group = #element_group;
+                                        for field in fields.iter().filter(|field| !field.hidden) {
+                                            inputs.enable_param(element_group.name(&field.name), index < len);
                                         }
                                     }
                                 }
+                                self.#field_name.resize_with(len, Default::default);
+                                for (index, element) in self.#field_name.iter_mut().enumerate() {
+                                    #base::OperatorParams::update_group(element, inputs, #element_group);
+                                }
+                            }
+                        },
+                    ));
+                    describe_code.push(quote! {
+                        for index in 0..#max {
+                            for mut field in <#element_type as #base::OperatorParams>::describe_group(#element_group) {
+                                field.field = format!("{}[{}].{}", #field_str, index, field.field);
+                                schema.push(field);
                             }
                         }
-                    }
+                    });
+                    preset_code.push(quote! {
+                        for (index, element) in self.#field_name.iter().enumerate() {
+                            #base::OperatorParams::preset_group(element, preset, #element_group);
+                        }
+                    });
+                    sequence_apply_code.push(quote! {
+                        {
+                            let len = #len;
+                            self.#field_name.resize_with(len, Default::default);
+                            for (index, element) in self.#field_name.iter_mut().enumerate() {
+                                #base::OperatorParams::apply_preset_group(element, preset, #element_group, errors);
+                            }
+                        }
+                    });
+                    continue;
                 }
 
-                let field_name_upper = format_name(&field_name.to_string());
+                if attrs.flatten {
+                    let prefix = attrs.prefix.unwrap_or_default();
+                    param_names.push(quote! {
+                        #base::ParamName::Group(#prefix, <#field_type as #base::ParamNames>::PARAM_NAMES)
+                    });
+                    let page = match &attrs.page {
+                        Some(page) => quote! { Some(#page) },
+                        None => quote! { None },
+                    };
+                    let group = quote! { group.nested(#prefix, #page) };
+                    register_code.push(quote! {
+                        #base::OperatorParams::register_group(&mut self.#field_name, parameter_manager, #group);
+                    });
+                    end_cook_code.push(quote! {
+                        #base::OperatorParams::end_cook(&mut self.#field_name);
+                    });
+                    update_code.push(track_changes(
+                        changes_field,
+                        index,
+                        quote! { #base::OperatorParams::update_group(&mut self.#field_name, inputs, #group) },
+                    ));
+                    describe_code.push(quote! {
+                        for mut field in <#field_type as #base::OperatorParams>::describe_group(#group) {
+                            field.field = format!("{}.{}", #field_str, field.field);
+                            schema.push(field);
+                        }
+                    });
+                    preset_code.push(quote! {
+                        #base::OperatorParams::preset_group(&self.#field_name, preset, #group);
+                    });
+                    apply_preset_code.push(quote! {
+                        #base::OperatorParams::apply_preset_group(&mut self.#field_name, preset, #group, errors);
+                    });
+                    continue;
+                }
+
+                let field_name_upper = match attrs.name {
+                    Some(name) => name,
+                    None => format_name(&field_str),
+                };
+                if !attr::is_valid_name(&field_name_upper) {
+                    errors.push(syn::Error::new(
+                        field_name.span(),
+                        format!(
+                            "`{}` is not a valid parameter name, rename the field or set one with `#[param(name = \"...\")]`",
+                            field_name_upper
+                        ),
+                    ));
+                }
+                if let Some(other) = names.insert(field_name_upper.clone(), field_name) {
+                    errors.push(syn::Error::new(
+                        field_name.span(),
+                        format!(
+                            "duplicate parameter name `{}`, also used by `{}`",
+                            field_name_upper, other
+                        ),
+                    ));
+                }
+                param_names.push(quote! { #base::ParamName::Name(#field_name_upper) });
                 let default_label = format!("{}", field_name);
-                let label = label.unwrap_or(default_label);
+                let label = attrs.label.unwrap_or(default_label);
                 let default_page = "Custom".to_string();
-                let page = page.unwrap_or(default_page);
-                let min = min.unwrap_or(0.0);
-                let max = max.unwrap_or(1.0);
-                let min_slider = min_slider.unwrap_or(min);
-                let max_slider = max_slider.unwrap_or(max);
-                let clamp = clamp.unwrap_or(false);
-                let default = default.unwrap_or(0.0);
-
-                let register_field_code = quote! {
+                let page = attrs.page.unwrap_or(default_page);
+                // Integer fields default to the range of their type, and
+                // are clamped to it unless a min or max is given.
+                let type_range = quote! { <#field_type as #base::Param>::type_range() };
+                let min = match attrs.min {
+                    Some(min) => quote! { #min },
+                    None => quote! { #type_range.map_or(0.0, |(min, _)| min) },
+                };
+                let max = match attrs.max {
+                    Some(max) => quote! { #max },
+                    None => quote! { #type_range.map_or(1.0, |(_, max)| max) },
+                };
+                // Sliders of signed integers start at zero rather than far
+                // below it.
+                let min_slider = match (attrs.min_slider, attrs.min) {
+                    (Some(min_slider), _) => quote! { #min_slider },
+                    (None, Some(min)) => quote! { #min },
+                    (None, None) => quote! { #type_range.map_or(0.0, |(min, _)| min.max(0.0)) },
+                };
+                let max_slider = match attrs.max_slider {
+                    Some(max_slider) => quote! { #max_slider },
+                    None => max.clone(),
+                };
+                let clamp = match attrs.clamp {
+                    Some(clamp) => quote! { #clamp },
+                    None if attrs.min.is_none() && attrs.max.is_none() => {
+                        quote! { #type_range.is_some() }
+                    }
+                    None => quote! { false },
+                };
+                let style = match &attrs.style {
+                    Some(style) => {
+                        // Give the path the field's span too, so a failed
+                        // check points at the field rather than the derive.
+                        let spanned_base: proc_macro2::TokenStream = base
+                            .clone()
+                            .into_iter()
+                            .map(|mut token| {
+                                token.set_span(field_type.span());
+                                token
+                            })
+                            .collect();
+                        style_checks.push(quote_spanned! {field_type.span()=>
+                            const _: () = #spanned_base::check_param_style::<#field_type>(#spanned_base::ParamStyle::#style);
+                        });
+                        quote! { Some(#base::ParamStyle::#style) }
+                    }
+                    None => quote! { None },
+                };
+                let default = attrs
+                    .default
+                    .as_ref()
+                    .and_then(|default| attr::number(default).ok())
+                    .unwrap_or(0.0);
+
+                let help = match &attrs.help {
+                    Some(help) => quote! { Some(#help.to_string()) },
+                    None => quote! { None },
+                };
+                let section = attrs.section;
+                let readonly = attrs.readonly;
+                let hidden = attrs.hidden;
+
+                let options_code = quote! {
+                    #base::ParamOptions {
+                        name: group.name(#field_name_upper).to_string(),
+                        label: #label.to_string(),
+                        page: group.page(#page).to_string(),
+                        min: #min,
+                        max: #max,
+                        min_slider: #min_slider,
+                        max_slider: #max_slider,
+                        clamp: #clamp,
+                        default: #default,
+                        style: #style,
+                        help: #help,
+                        section: #section,
+                        readonly: #readonly,
+                        hidden: #hidden,
+                    }
+                };
+
+                // A `default` attribute is converted to a value of the
+                // field's type, which is registered in place of the field's
+                // current value.
+                let typed_default = attrs
+                    .default
+                    .as_ref()
+                    .map(|default| default_value(field_type, default));
+
+                // Menu entries are read from the field, so they are kept
+                // when a default is registered in its place.
+                let is_string_menu = is_type(field_type, &["StringMenu"]);
+                let entries_code = attrs.entries.as_ref().map(|method| {
+                    if !is_string_menu {
+                        errors.push(syn::Error::new(
+                            method.span(),
+                            "`entries` can only be used on `StringMenu` fields",
+                        ));
+                    }
+                    quote! { self.#field_name.set_entries(self.#method()); }
+                });
+                let default_entries_code = is_string_menu.then(|| {
+                    quote! { let default = default.with_entries(self.#field_name.entries().iter().cloned()); }
+                });
+
+                let register_field_code = match &typed_default {
+                    Some(default) => quote! {
+                        {
+                            #entries_code
+                            let options = #options_code;
+                            let default: #field_type = #default;
+                            #default_entries_code
+                            #base::Param::register(&default, options, parameter_manager);
+                        }
+                    },
+                    None => quote! {
+                        {
+                            #entries_code
+                            let options = #options_code;
+                            #base::Param::register(&self.#field_name, options, parameter_manager);
+                        }
+                    },
+                };
+                // Hidden fields are only described and saved in presets.
+                if !hidden {
+                    register_code.push(register_field_code);
+                }
+
+                let descriptor_code = match &typed_default {
+                    Some(default) => quote! {
+                        {
+                            let default: #field_type = #default;
+                            #base::Param::descriptor(&default, &options)
+                        }
+                    },
+                    None => quote! { <#field_type as #base::Param>::describe(&options) },
+                };
+                let describe_field_code = quote! {
                     {
-                        let options = ParamOptions {
-                            name: #field_name_upper.to_string(),
-                            label: #label.to_string(),
-                            page: #page.to_string(),
-                            min: #min,
-                            max: #max,
-                            min_slider: #min_slider,
-                            max_slider: #max_slider,
-                            clamp: #clamp,
-                            default: #default,
-                        };
-                        Param::register(&self.#field_name, options, parameter_manager);
+                        let options = #options_code;
+                        let descriptor = #descriptor_code;
+                        schema.push(#base::ParamSchema::new(#field_str, &options, descriptor));
                     }
                 };
-                register_code.push(register_field_code);
+                describe_code.push(describe_field_code);
 
                 let update_field_code = quote! {
-                    // TODO: Field name should be null terminated
-                    Param::update(&mut self.#field_name, &(#field_name_upper.to_string()), inputs);
+                    #base::Param::update(&mut self.#field_name, group.name(#field_name_upper), inputs)
                 };
 
-                update_code.push(update_field_code);
+                if !hidden {
+                    update_code.push(track_changes(changes_field, index, update_field_code));
+                }
+                // The SDK can't make a parameter read-only, so it is disabled.
+                if readonly && !hidden {
+                    enable_code.push(quote! {
+                        inputs.enable_param(group.name(#field_name_upper), false);
+                    });
+                }
+
+                preset_code.push(quote! {
+                    if let Some(value) = #base::Param::preset_value(&self.#field_name) {
+                        preset.insert(group.name(#field_name_upper), value);
+                    }
+                });
+                apply_preset_code.push(quote! {
+                    if let (Some(value), Some(_)) = (
+                        preset.get(group.name(#field_name_upper)),
+                        #base::Param::preset_value(&self.#field_name),
+                    ) {
+                        let options = #options_code;
+                        if let Err(err) = #base::PresetError::check_range(&options.name, value, &options)
+                            .and_then(|_| #base::Param::apply_preset_value(&mut self.#field_name, &options.name, value))
+                        {
+                            errors.push(err);
+                        }
+                    }
+                });
+
+                if let Some(method) = &attrs.on_pulse {
+                    if !is_type(field_type, &["Pulse"]) {
+                        errors.push(syn::Error::new(
+                            method.span(),
+                            "`on_pulse` can only be used on `Pulse` fields",
+                        ));
+                    } else if struct_attrs.op.is_none() {
+                        errors.push(syn::Error::new(
+                            method.span(),
+                            "`on_pulse` requires the operator to be named with `#[params(op = ...)]`",
+                        ));
+                    }
+                    pulse_code.push(quote! {
+                        if name == #field_name_upper {
+                            self.#method();
+                        }
+                    });
+                }
+
+                if let Some(enable_if) = &attrs.enable_if {
+                    match condition::expand(enable_if, &fields) {
+                        Ok(condition) => enable_code.push(quote! {
+                            inputs.enable_param(group.name(#field_name_upper), #condition);
+                        }),
+                        Err(err) => errors.push(err),
+                    }
+                }
             }
         }
     }
 
+    if let Some(err) = attr::combine(errors) {
+        return err.to_compile_error().into();
+    }
+
     let register_code = quote! { #(#register_code)* };
 
+    // Names in flattened groups are only known once they are compiled, so
+    // they are checked in a constant, as are the sizes of styled fields.
+    let check_names = input.generics.params.is_empty().then(|| {
+        quote! {
+            const _: () = #base::check_param_names(<#struct_name as #base::ParamNames>::PARAM_NAMES);
+            #(#style_checks)*
+        }
+    });
+
+    let pulse_handler = struct_attrs.op.as_ref().map(|op| {
+        quote! {
+            impl #op {
+                /// Call the `on_pulse` method of the pulse parameter named
+                /// `name`. Forward `Op::dispatch_pulse` to this.
+                pub(crate) fn dispatch_on_pulse(&mut self, name: &str) {
+                    #(#pulse_code)*
+                }
+            }
+        }
+    });
+
+    let begin_changes = changes_field.map(|changes| {
+        let len = changed_fields.len();
+        quote! { self.#changes.begin(#len); }
+    });
+    let end_changes = changes_field.map(|changes| quote! { self.#changes.end_cook(); });
+    let changed_code = changes_field.map(|changes| {
+        let vis = &input.vis;
+        let changed_name = format_ident!("{}Changed", struct_name);
+        let doc = format!(
+            "Which fields of [`{}`] changed since its operator last cooked.",
+            struct_name
+        );
+        let indices = 0..changed_fields.len();
+        let field_strs = changed_fields.iter().map(|field| field.to_string());
+        quote! {
+            #[doc = #doc]
+            #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+            #vis struct #changed_name {
+                #(pub #changed_fields: bool,)*
+            }
+
+            impl #changed_name {
+                /// Whether any field changed.
+                pub fn any(&self) -> bool {
+                    false #(|| self.#changed_fields)*
+                }
+
+                /// The names of the fields which changed.
+                pub fn fields(&self) -> impl Iterator<Item = &'static str> {
+                    [#((#field_strs, self.#changed_fields)),*]
+                        .into_iter()
+                        .filter_map(|(field, changed)| changed.then_some(field))
+                }
+            }
+
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                /// Which fields changed since the operator last cooked.
+                pub fn changed(&self) -> #changed_name {
+                    #changed_name {
+                        #(#changed_fields: self.#changes.is_changed(#indices),)*
+                    }
+                }
+            }
+        }
+    });
+
+    let serde_code = struct_attrs.serde.then(|| {
+        let mut de_generics = input.generics.clone();
+        de_generics.params.insert(0, syn::parse_quote!('de));
+        let (de_impl_generics, _, _) = de_generics.split_for_impl();
+        quote! {
+            impl #impl_generics ::serde::Serialize for #struct_name #ty_generics #where_clause {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: ::serde::Serializer,
+                {
+                    ::serde::Serialize::serialize(&#base::OperatorParams::to_preset(self), serializer)
+                }
+            }
+
+            // Parameters missing from the input keep their default values.
+            impl #de_impl_generics ::serde::Deserialize<'de> for #struct_name #ty_generics #where_clause {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: ::serde::Deserializer<'de>,
+                {
+                    let preset = <#base::Preset as ::serde::Deserialize>::deserialize(deserializer)?;
+                    let mut params = <Self as Default>::default();
+                    params.apply_preset(&preset).map_err(|errors| {
+                        let errors = errors.iter().map(ToString::to_string).collect::<Vec<_>>();
+                        <D::Error as ::serde::de::Error>::custom(errors.join(", "))
+                    })?;
+                    Ok(params)
+                }
+            }
+        }
+    });
+
     let gen = quote! {
-        impl #impl_generics OperatorParams for #struct_name #ty_generics #where_clause {
-            fn register(&mut self, parameter_manager: &mut ParameterManager) {
+        impl #impl_generics #base::OperatorParams for #struct_name #ty_generics #where_clause {
+            fn register(&mut self, parameter_manager: &mut #base::ParameterManager) {
+                self.register_group(parameter_manager, #base::ParamGroup::default());
+            }
+
+            fn update(&mut self, inputs: &#base::ParamInputs) {
+                self.update_group(inputs, #base::ParamGroup::default());
+            }
+
+            fn describe() -> &'static [#base::ParamSchema]
+            where
+                Self: Sized + 'static,
+            {
+                #base::ParamSchema::cached::<Self>(|| Self::describe_group(#base::ParamGroup::default()))
+            }
+
+            fn register_group(&mut self, parameter_manager: &mut #base::ParameterManager, group: #base::ParamGroup) {
                 #register_code
             }
 
-            fn update(&mut self, inputs: &ParamInputs) {
+            fn update_group(&mut self, inputs: &#base::ParamInputs, group: #base::ParamGroup) {
+                #begin_changes
                 #(#update_code)*
+                #(#sequence_update_code)*
+                #(#enable_code)*
+                #(#hook_code)*
+            }
+
+            fn end_cook(&mut self) {
+                #end_changes
+                #(#end_cook_code)*
+            }
+
+            fn describe_group(group: #base::ParamGroup) -> Vec<#base::ParamSchema>
+            where
+                Self: Sized + 'static,
+            {
+                let mut schema = Vec::new();
+                #(#describe_code)*
+                schema
+            }
+
+            fn preset_group(&self, preset: &mut #base::Preset, group: #base::ParamGroup) {
+                #(#preset_code)*
+            }
+
+            fn apply_preset_group(
+                &mut self,
+                preset: &#base::Preset,
+                group: #base::ParamGroup,
+                errors: &mut Vec<#base::PresetError>,
+            ) {
+                #(#apply_preset_code)*
+                #(#sequence_apply_code)*
             }
         }
+
+        impl #impl_generics #base::ParamNames for #struct_name #ty_generics #where_clause {
+            const PARAM_NAMES: &'static [#base::ParamName] = &[#(#param_names),*];
+        }
+
+        #check_names
+
+        #changed_code
+
+        #pulse_handler
+
+        #serde_code
     };
     gen.into()
 }
 
+// Crates which export td-rs-base's items, in the order they're looked for.
+const BASE_CRATES: &[&str] = &[
+    "td-rs-base",
+    "td-rs-chop",
+    "td-rs-dat",
+    "td-rs-sop",
+    "td-rs-top",
+];
+
+/// The path to td-rs-base's items from the crate using the derive. Plugins
+/// usually only depend on one of the operator crates, which re-export them,
+/// so this looks for the first of [`BASE_CRATES`] in the crate's manifest,
+/// under whatever name it's been given there.
+fn base_path() -> proc_macro2::TokenStream {
+    let manifest = std::env::var("CARGO_MANIFEST_DIR")
+        .ok()
+        .and_then(|dir| std::fs::read_to_string(std::path::Path::new(&dir).join("Cargo.toml")).ok())
+        .and_then(|manifest| manifest.parse::<toml::Table>().ok())
+        .unwrap_or_default();
+    let mut names = HashMap::new();
+    for table in ["dependencies", "dev-dependencies"] {
+        let Some(deps) = manifest.get(table).and_then(toml::Value::as_table) else {
+            continue;
+        };
+        for (name, dep) in deps {
+            let package = dep
+                .get("package")
+                .and_then(toml::Value::as_str)
+                .unwrap_or(name);
+            names
+                .entry(package.to_string())
+                .or_insert(name.replace('-', "_"));
+        }
+    }
+    let name = BASE_CRATES
+        .iter()
+        .find_map(|krate| names.get(*krate))
+        .map_or("td_rs_base", String::as_str);
+    let ident = Ident::new(name, proc_macro2::Span::call_site());
+    quote! { ::#ident }
+}
+
+/// Wrap a field's `update`, recording whether it changed when the struct has
+/// a `ParamChanges` field.
+fn track_changes(
+    changes: Option<&Ident>,
+    index: usize,
+    update: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match changes {
+        Some(changes) => quote! {
+            {
+                let hash = inputs.hash_reads(|| #update);
+                self.#changes.record(#index, hash);
+            }
+        },
+        None => quote! { #update; },
+    }
+}
+
+// Types whose string defaults are never menu variants.
+const STRING_TYPES: &[&str] = &[
+    "String",
+    "PathBuf",
+    "FileParam",
+    "FolderParam",
+    "StringMenu",
+];
+
+/// Build an expression of type `ty` from the `default` attribute. Number and
+/// bool literals are used as is so the compiler checks them against the
+/// field, a string naming a variant selects that variant of a menu, and
+/// anything else is converted with `From`.
+fn default_value(ty: &Type, default: &Expr) -> proc_macro2::TokenStream {
+    let span = default.span();
+    if let Ok(number) = attr::number(default) {
+        // Allow integer literals for float fields
+        if is_type(ty, &["f32", "f64"]) {
+            let literal = proc_macro2::Literal::f64_unsuffixed(number.abs());
+            return if number < 0.0 {
+                quote_spanned!(span=> -#literal)
+            } else {
+                quote_spanned!(span=> #literal)
+            };
+        }
+        return quote_spanned!(span=> #default);
+    }
+    match default {
+        Expr::Lit(ExprLit {
+            lit: Lit::Bool(_), ..
+        }) => quote_spanned!(span=> #default),
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(lit), ..
+        }) if !is_type(ty, STRING_TYPES) && is_variant_name(&lit.value()) => {
+            let variant = Ident::new(&lit.value(), lit.span());
+            quote_spanned!(span=> <#ty>::#variant)
+        }
+        _ => quote_spanned!(span=> ::std::convert::From::from(#default)),
+    }
+}
+
+/// The type of the elements of a `Vec`.
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Vec" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_type(ty: &Type, names: &[&str]) -> bool {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map_or(false, |segment| {
+            names.iter().any(|name| segment.ident == name)
+        }),
+        _ => false,
+    }
+}
+
+fn is_variant_name(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().map_or(false, |c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Make a TouchDesigner parameter name from a field name, e.g. `min_cutoff`
+/// becomes `Mincutoff`.
 fn format_name(name: &str) -> String {
-    let name = remove_underscores(name);
+    let name = remove_underscores(name).to_lowercase();
     capitalize_first(&name)
 }
 
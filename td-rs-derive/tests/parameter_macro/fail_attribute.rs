@@ -0,0 +1,67 @@
+#![allow(unused)]
+
+use td_rs_base::*;
+use td_rs_derive::*;
+
+#[derive(Params)]
+struct UnknownAttribute {
+    #[param(speed = 1.0)]
+    speed: f32,
+    #[param(min = "low")]
+    scale: f32,
+}
+
+#[derive(Params, Default)]
+struct Group {
+    gain: f32,
+}
+
+#[derive(Params)]
+struct FlattenAttribute {
+    #[param(flatten, min = 0.0)]
+    group: Group,
+    #[param(prefix = "Extra")]
+    extra: f32,
+}
+
+#[derive(Params)]
+struct UntrackedChange {
+    #[param(on_change = "reconnect")]
+    prefix: String,
+}
+
+#[derive(Params)]
+struct UnnamedOp {
+    #[param(on_pulse = "reset")]
+    reset: Pulse,
+    #[param(on_pulse = "reset")]
+    speed: f32,
+}
+
+#[derive(Params)]
+struct MenuEntries {
+    #[param(entries = "devices")]
+    device: String,
+}
+
+#[derive(Params)]
+struct ReadonlyCondition {
+    apply_scale: bool,
+    #[param(readonly, enable_if = "apply_scale")]
+    scale: f32,
+}
+
+#[derive(Params)]
+struct Sequences {
+    count: u8,
+    #[param(count = "count")]
+    unbounded: Vec<Group>,
+    #[param(count = "absent", max_count = 2)]
+    missing: Vec<Group>,
+    #[param(count = 2)]
+    not_vec: Group,
+    #[param(count = 2, min = 1.0)]
+    ranged: Vec<Group>,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#![allow(unused)]
+
+use td_rs_base::*;
+use td_rs_derive::*;
+
+#[derive(Param)]
+enum Mode {
+    Fast,
+    Slow,
+}
+
+#[derive(Params)]
+struct UnknownField {
+    #[param(enable_if = "apply_scale")]
+    scale: f32,
+    #[param(enable_if = "scale > 1.0")]
+    offset: f32,
+}
+
+#[derive(Params)]
+struct MistypedField {
+    mode: Mode,
+    #[param(enable_if = "mode")]
+    scale: f32,
+    #[param(enable_if = "mode == Medium")]
+    offset: f32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use td_rs_derive::*;
+
+#[derive(Params)]
+struct WrongDefaults {
+    #[param(default = "fast")]
+    speed: f32,
+    #[param(default = true)]
+    count: i32,
+}
+
+fn main() {}
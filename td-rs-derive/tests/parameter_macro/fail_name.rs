@@ -0,0 +1,36 @@
+#![allow(unused)]
+
+use td_rs_base::*;
+use td_rs_derive::*;
+
+#[derive(Params)]
+struct DuplicateNames {
+    a_b: f32,
+    ab: f32,
+    #[param(name = "AB")]
+    c: f32,
+    #[param(name = "Ab")]
+    d: f32,
+}
+
+#[derive(Params, Default)]
+struct Group {
+    gain: f32,
+}
+
+#[derive(Params)]
+struct DuplicateGroupNames {
+    #[param(flatten, prefix = "Blur")]
+    first: Group,
+    #[param(flatten, prefix = "Blur")]
+    second: Group,
+}
+
+#[derive(Params, Default)]
+struct DuplicateSequenceNames {
+    gain2: f32,
+    #[param(count = 3)]
+    bands: Vec<Group>,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+#![allow(unused)]
+
+use td_rs_base::*;
+use td_rs_derive::*;
+
+#[derive(Params)]
+#[params(op = ResetOp)]
+struct ResetParams {
+    #[param(on_pulse = "reste")]
+    reset: Pulse,
+}
+
+struct ResetOp {
+    params: ResetParams,
+}
+
+impl ResetOp {
+    fn reset(&mut self) {}
+}
+
+fn main() {}
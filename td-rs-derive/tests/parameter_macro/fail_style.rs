@@ -0,0 +1,18 @@
+#![allow(unused)]
+
+use td_rs_base::*;
+use td_rs_derive::*;
+
+#[derive(Params)]
+struct MismatchedStyle {
+    #[param(style = "uv")]
+    position: [f32; 3],
+}
+
+#[derive(Params)]
+struct UnsupportedStyle {
+    #[param(style = "xyz")]
+    cells: [i32; 3],
+}
+
+fn main() {}
@@ -23,6 +23,195 @@ struct TestParameter {
     // rgb: rgb::RGB<u8>,
 }
 
+#[derive(Params)]
+struct TestDefaults {
+    #[param(default = true)]
+    toggle: bool,
+    #[param(default = "/touchdesigner")]
+    prefix: String,
+    #[param(default = 3)]
+    count: u8,
+    #[param(default = 1)]
+    speed: f32,
+    #[param(default = -0.5)]
+    offset: f64,
+    #[param(default = "Hello")]
+    menu: TestEnum,
+}
+
+#[derive(Params)]
+struct TestVectors {
+    #[param(default = [1.0, 2.0, 3.0])]
+    offset: [f32; 3],
+    #[param(style = "uv", default = (0.5, 0.5))]
+    center: (f64, f64),
+    #[param(style = "rgba")]
+    tint: [f32; 4],
+    size: [i32; 2],
+}
+
+#[derive(Params)]
+struct TestGroup {
+    #[param(page = "Blur")]
+    size: f32,
+    #[param(flatten, prefix = "Inner")]
+    vectors: TestVectors,
+}
+
+#[derive(Params)]
+struct TestFlatten {
+    gain: f32,
+    #[param(flatten, prefix = "Blur", page = "Effects")]
+    blur: TestGroup,
+    #[param(flatten)]
+    defaults: TestDefaults,
+}
+
+#[derive(Params)]
+struct TestConditions {
+    apply_scale: bool,
+    #[param(enable_if = "apply_scale")]
+    scale: f32,
+    menu: TestEnum,
+    #[param(enable_if = "menu == Hello || (apply_scale && menu != TestEnum::Hi)")]
+    greeting: String,
+    #[param(enable_if = "!input(0)")]
+    speed: f32,
+}
+
+#[derive(Params, Default)]
+struct TestBlur {
+    size: f32,
+    changes: ParamChanges,
+}
+
+#[derive(Params, Default)]
+struct TestChanges {
+    #[param(on_change = "reconnect")]
+    prefix: String,
+    hold: bool,
+    #[param(flatten, prefix = "Blur", on_change = "reconnect")]
+    blur: TestBlur,
+    changes: ParamChanges,
+}
+
+impl TestChanges {
+    fn reconnect(&mut self) {}
+}
+
+#[derive(Params, Default)]
+#[params(op = TestOp)]
+struct TestPulses {
+    #[param(on_pulse = "reset")]
+    reset: Pulse,
+    hold: Momentary,
+}
+
+#[derive(Default)]
+struct TestOp {
+    params: TestPulses,
+    resets: u32,
+}
+
+impl TestOp {
+    fn reset(&mut self) {
+        self.resets += 1;
+    }
+}
+
+impl Op for TestOp {
+    fn params_mut(&mut self) -> Option<Box<&mut dyn OperatorParams>> {
+        Some(Box::new(&mut self.params))
+    }
+
+    fn dispatch_pulse(&mut self, name: &str) {
+        self.dispatch_on_pulse(name);
+    }
+}
+
+#[derive(Params, Default)]
+struct TestMenus {
+    #[param(entries = "devices", default = "m1000")]
+    device: StringMenu,
+    model: StringMenu,
+}
+
+impl TestMenus {
+    fn devices(&self) -> Vec<String> {
+        vec!["m1000".to_string(), "m2000".to_string()]
+    }
+}
+
+#[derive(Params)]
+struct TestNames {
+    min_cutoff: f32,
+    #[param(name = "Beta2")]
+    beta: f32,
+}
+
+#[derive(Params, Default)]
+#[params(serde)]
+struct TestPresets {
+    #[param(min = 0, max = 10, clamp = true)]
+    gain: f32,
+    menu: TestEnum,
+    #[param(flatten, prefix = "Blur")]
+    blur: TestBlur,
+}
+
+#[derive(Params, Default)]
+struct TestOpRefs {
+    #[param(label = "Operators")]
+    header: Header,
+    op: OpParam,
+    comp: CompParam,
+    mat: MatParam,
+    panel: PanelCompParam,
+    object: ObjectParam,
+    hold: Momentary,
+}
+
+#[derive(Params)]
+struct TestInts {
+    seed: u16,
+    offset: i64,
+    #[param(min = -5, max = 300, default = 7)]
+    count: i16,
+    #[param(max = u8::MAX, max_slider = 100)]
+    level: u8,
+}
+
+#[derive(Params, Default)]
+struct TestLayout {
+    #[param(help = "Frames per second", section)]
+    rate: f32,
+    #[param(readonly)]
+    status: String,
+    #[param(hidden)]
+    calibration: f32,
+}
+
+#[derive(Params, Default)]
+struct TestBand {
+    gain: f32,
+    freq: f32,
+}
+
+#[derive(Params, Default)]
+struct TestSequences {
+    #[param(count = 3)]
+    bands: Vec<TestBand>,
+    layer_count: u8,
+    #[param(count = "layer_count", max_count = 4, prefix = "Layer")]
+    layers: Vec<TestBlur>,
+}
+
+impl Default for TestEnum {
+    fn default() -> Self {
+        TestEnum::Hi
+    }
+}
+
 fn main() {
     let mut param = TestParameter {
         // Initialize fields
@@ -43,4 +232,241 @@ fn main() {
             String::from("Goodbye")
         ]
     );
+
+    let schema = TestParameter::describe();
+    assert_eq!(schema.len(), 7);
+    assert_eq!(schema[0].field, "float2");
+    assert_eq!(schema[0].name, "Float2");
+    assert_eq!(schema[0].label, "Hi");
+    assert_eq!(schema[6].descriptor.entries().len(), 3);
+
+    let schema = TestDefaults::describe();
+    assert_eq!(
+        schema[0].descriptor,
+        ParameterDescriptor::Toggle { default: true }
+    );
+    assert_eq!(
+        schema[1].descriptor,
+        ParameterDescriptor::String {
+            default: "/touchdesigner".to_string()
+        }
+    );
+    let defaults = |i: usize| schema[i].descriptor.numeric().unwrap().default_values[0];
+    assert_eq!(defaults(2), 3.0);
+    assert_eq!(defaults(3), 1.0);
+    assert_eq!(defaults(4), -0.5);
+    match &schema[5].descriptor {
+        ParameterDescriptor::Menu { default, .. } => assert_eq!(default, "Hello"),
+        _ => panic!("expected a menu"),
+    }
+
+    let schema = TestVectors::describe();
+    match &schema[0].descriptor {
+        ParameterDescriptor::Xyz(options) => {
+            assert_eq!(options.default_values, [1.0, 2.0, 3.0, 0.0])
+        }
+        _ => panic!("expected an xyz"),
+    }
+    match &schema[1].descriptor {
+        ParameterDescriptor::Uv(options) => assert_eq!(options.default_values[..2], [0.5, 0.5]),
+        _ => panic!("expected a uv"),
+    }
+    assert!(matches!(schema[2].descriptor, ParameterDescriptor::Rgba(_)));
+    assert!(matches!(
+        schema[3].descriptor,
+        ParameterDescriptor::Int { size: 2, .. }
+    ));
+
+    let schema = TestFlatten::describe();
+    assert_eq!(schema.len(), 12);
+    assert_eq!(schema[1].field, "blur.size");
+    assert_eq!(schema[1].name, "Blursize");
+    assert_eq!(schema[1].page, "Effects");
+    assert_eq!(schema[2].field, "blur.vectors.offset");
+    assert_eq!(schema[2].name, "Blurinneroffset");
+    assert_eq!(schema[2].page, "Effects");
+    assert_eq!(schema[6].field, "defaults.toggle");
+    assert_eq!(schema[6].name, "Toggle");
+    assert_eq!(schema[6].page, "Custom");
+
+    let changes = TestChanges::default();
+    assert!(!changes.changed().any());
+    assert!(!changes.changed().prefix);
+    assert_eq!(changes.changed().fields().count(), 0);
+    assert_eq!(TestChanges::describe().len(), 3);
+
+    // Pulses are dispatched through the trait object the operator holds.
+    let mut op = TestOp::default();
+    let dyn_op: &mut dyn Op = &mut op;
+    dyn_op.dispatch_pulse("Reset");
+    dyn_op.dispatch_pulse("Hold");
+    assert_eq!(op.resets, 1);
+    assert_eq!(
+        TestPulses::describe()[1].descriptor,
+        ParameterDescriptor::Momentary
+    );
+
+    let schema = TestMenus::describe();
+    assert_eq!(
+        schema[0].descriptor,
+        ParameterDescriptor::StringMenu {
+            default: "m1000".to_string(),
+            entries: vec![],
+        }
+    );
+    let menu = StringMenu::from("b.ckpt").with_entries(["a.ckpt", "b.ckpt"]);
+    assert_eq!(menu.entries()[0], MenuEntry::new("a.ckpt", "a.ckpt"));
+    assert_eq!(menu.selected(), Some(&menu.entries()[1]));
+    assert_eq!(menu.as_str(), "b.ckpt");
+
+    let schema = TestNames::describe();
+    assert_eq!(schema[0].name, "Mincutoff");
+    assert_eq!(schema[1].name, "Beta2");
+
+    let mut presets = TestPresets::default();
+    presets.gain = 2.0;
+    presets.menu = TestEnum::Goodbye;
+    let preset = presets.to_preset();
+    assert_eq!(preset.get("Gain"), Some(&PresetValue::Number(2.0)));
+    assert_eq!(
+        preset.get("Menu"),
+        Some(&PresetValue::String("Goodbye".to_string()))
+    );
+    assert_eq!(preset.get("Blursize"), Some(&PresetValue::Number(0.0)));
+
+    let other = TestPresets::default().to_preset();
+    let diff = preset.diff(&other);
+    assert_eq!(diff.len(), 2);
+    assert_eq!(diff[0].name, "Gain");
+    let half = preset.lerp(&other, 0.5);
+    assert_eq!(half.get("Gain"), Some(&PresetValue::Number(1.0)));
+    assert_eq!(
+        half.get("Menu"),
+        Some(&PresetValue::String("Hi".to_string()))
+    );
+
+    let mut applied = TestPresets::default();
+    assert_eq!(applied.apply_preset(&preset), Ok(()));
+    assert_eq!(applied.gain, 2.0);
+    assert!(matches!(applied.menu, TestEnum::Goodbye));
+
+    let mut bad = Preset::new();
+    bad.insert("Gain", PresetValue::Number(20.0));
+    bad.insert("Menu", PresetValue::Bool(true));
+    bad.insert("Missing", PresetValue::Number(1.0));
+    let errors = applied.apply_preset(&bad).unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert!(errors.contains(&PresetError::Unknown {
+        name: "Missing".to_string()
+    }));
+    assert!(errors.contains(&PresetError::OutOfRange {
+        name: "Gain".to_string(),
+        value: 20.0,
+        min: 0.0,
+        max: 10.0,
+    }));
+    assert_eq!(applied.gain, 2.0);
+
+    let mut ints = Preset::new();
+    ints.insert("Int", PresetValue::Number(1.5));
+    ints.insert("Int2", PresetValue::Number(-1.0));
+    let errors = param.apply_preset(&ints).unwrap_err();
+    assert_eq!(
+        errors,
+        vec![
+            PresetError::WrongType {
+                name: "Int".to_string(),
+                expected: "a whole number",
+                found: "a fraction",
+            },
+            PresetError::OutOfRange {
+                name: "Int2".to_string(),
+                value: -1.0,
+                min: 0.0,
+                max: i32::MAX as f64,
+            },
+        ]
+    );
+    assert_eq!((param.int, param.int2), (0, 0));
+
+    let json = serde_json::to_string(&presets).unwrap();
+    assert_eq!(Preset::from_json(&json), Ok(preset));
+    let loaded: TestPresets = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded.gain, 2.0);
+    assert!(serde_json::from_str::<TestPresets>(r#"{"Gain": 20}"#).is_err());
+
+    let schema = TestOpRefs::describe();
+    assert_eq!(schema[0].descriptor, ParameterDescriptor::Header);
+    assert_eq!(schema[0].label, "Operators");
+    let families = schema[1..6]
+        .iter()
+        .map(|field| match &field.descriptor {
+            ParameterDescriptor::OpRef { family, .. } => *family,
+            _ => panic!("expected an operator reference"),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        families,
+        [
+            OpFamily::Any,
+            OpFamily::Comp,
+            OpFamily::Mat,
+            OpFamily::PanelComp,
+            OpFamily::Object
+        ]
+    );
+    assert_eq!(TestOpRefs::default().comp.path(), "");
+    assert!(TestOpRefs::default().comp.object().is_none());
+
+    let schema = TestInts::describe();
+    let options = |i: usize| schema[i].descriptor.numeric().unwrap().clone();
+    assert_eq!(options(0).min_values[0], 0.0);
+    assert_eq!(options(0).max_values[0], 65535.0);
+    assert!(options(0).clamp_mins[0]);
+    assert_eq!(options(1).min_values[0], i32::MIN as f64);
+    assert_eq!(options(1).max_values[0], i32::MAX as f64);
+    assert_eq!(options(1).min_sliders[0], 0.0);
+    assert_eq!(options(2).min_values[0], -5.0);
+    assert_eq!(options(2).max_values[0], 300.0);
+    assert_eq!(options(2).default_values[0], 7.0);
+    assert!(!options(2).clamp_mins[0]);
+    assert_eq!(options(3).max_values[0], 255.0);
+    assert_eq!(options(3).max_sliders[0], 100.0);
+
+    let schema = TestLayout::describe();
+    assert_eq!(schema[0].help.as_deref(), Some("Frames per second"));
+    assert!(schema[0].section);
+    assert!(!schema[0].readonly);
+    assert!(schema[1].readonly);
+    assert!(schema[2].hidden);
+    assert!(TestLayout::default().to_preset().contains("Calibration"));
+
+    let schema = TestSequences::describe();
+    assert_eq!(schema.len(), 11);
+    assert_eq!(schema[0].name, "Gain1");
+    assert_eq!(schema[1].name, "Freq1");
+    assert_eq!(schema[2].name, "Gain2");
+    assert_eq!(schema[2].field, "bands[1].gain");
+    assert_eq!(schema[2].page, "Bands");
+    assert_eq!(schema[6].name, "Layercount");
+    assert_eq!(schema[10].name, "Layersize4");
+    assert_eq!(schema[10].page, "Layers");
+
+    let mut sequences = TestSequences::default();
+    let mut preset = Preset::new();
+    preset.insert("Gain3", PresetValue::Number(0.5));
+    preset.insert("Layercount", PresetValue::Number(2.0));
+    preset.insert("Layersize2", PresetValue::Number(4.0));
+    assert_eq!(sequences.apply_preset(&preset), Ok(()));
+    assert_eq!(sequences.bands.len(), 3);
+    assert_eq!(sequences.bands[2].gain, 0.5);
+    assert_eq!(sequences.layers.len(), 2);
+    assert_eq!(sequences.layers[1].size, 4.0);
+    preset.insert("Layersize3", PresetValue::Number(1.0));
+    assert_eq!(
+        sequences.apply_preset(&preset),
+        Err(vec![PresetError::Unknown {
+            name: "Layersize3".to_string()
+        }])
+    );
 }
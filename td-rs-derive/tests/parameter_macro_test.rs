@@ -9,4 +9,10 @@ fn parameter_macro_tests() {
 
     // Test case for expected error
     // t.compile_fail("tests/parameter_macro/fail.rs");
+    t.compile_fail("tests/parameter_macro/fail_attribute.rs");
+    t.compile_fail("tests/parameter_macro/fail_default.rs");
+    t.compile_fail("tests/parameter_macro/fail_condition.rs");
+    t.compile_fail("tests/parameter_macro/fail_pulse.rs");
+    t.compile_fail("tests/parameter_macro/fail_name.rs");
+    t.compile_fail("tests/parameter_macro/fail_style.rs");
 }
@@ -16,20 +16,79 @@ pub struct SopGeneralInfo {
 
 pub struct SopOutput<'execute> {
     output: Pin<&'execute mut cxx::SOP_Output>,
+    staged: Option<Staged>,
+}
+
+type StagedWrite = Box<dyn FnOnce(&mut SopOutput)>;
+
+/// Geometry held back from the output until execute succeeds, so that
+/// [`ErrorOutput::Empty`] can discard it. The counts stand in for the
+/// output's own while staged.
+#[derive(Default)]
+struct Staged {
+    writes: Vec<StagedWrite>,
+    num_points: usize,
+    num_primitives: usize,
+    normals: bool,
+    colors: bool,
+    tex_coord_layers: usize,
+    custom_attributes: bool,
+}
+
+impl Staged {
+    fn push(&mut self, write: impl FnOnce(&mut SopOutput) + 'static) {
+        self.writes.push(Box::new(write));
+    }
 }
 
 impl<'execute> SopOutput<'execute> {
     /// Create a new `SopOutput` from a pinning reference to a
     /// `SopOutput`.
     pub fn new(output: Pin<&'execute mut cxx::SOP_Output>) -> SopOutput<'execute> {
-        Self { output }
+        Self {
+            output,
+            staged: None,
+        }
+    }
+
+    /// Create a `SopOutput` which only writes to `output` once
+    /// [`SopOutput::commit`] is called.
+    pub(crate) fn staged(output: Pin<&'execute mut cxx::SOP_Output>) -> SopOutput<'execute> {
+        Self {
+            output,
+            staged: Some(Staged::default()),
+        }
+    }
+
+    /// Write any staged geometry to the output.
+    pub(crate) fn commit(&mut self) {
+        if let Some(staged) = self.staged.take() {
+            for write in staged.writes {
+                write(self);
+            }
+        }
     }
 
     pub fn add_point(&mut self, pos: impl Into<Position>) -> usize {
-        self.output.as_mut().addPoint(&pos.into()) as usize
+        let pos = pos.into();
+        if let Some(staged) = &mut self.staged {
+            let index = staged.num_points;
+            staged.num_points += 1;
+            staged.push(move |output| {
+                output.add_point(pos);
+            });
+            return index;
+        }
+        self.output.as_mut().addPoint(&pos) as usize
     }
 
     pub fn add_points(&mut self, positions: &[Position]) {
+        if let Some(staged) = &mut self.staged {
+            staged.num_points += positions.len();
+            let positions = positions.to_vec();
+            staged.push(move |output| output.add_points(&positions));
+            return;
+        }
         unsafe {
             self.output.as_mut().addPoints(
                 positions.as_ptr() as *const cxx::Position,
@@ -39,16 +98,31 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn num_points(&mut self) -> usize {
+        if let Some(staged) = &self.staged {
+            return staged.num_points;
+        }
         self.output.as_mut().getNumPoints() as usize
     }
 
     pub fn set_normal(&mut self, normal: impl Into<Vec3>, start_idx: usize) {
+        let normal = normal.into();
+        if let Some(staged) = &mut self.staged {
+            staged.normals = true;
+            staged.push(move |output| output.set_normal(normal, start_idx));
+            return;
+        }
         self.output
             .as_mut()
-            .setNormal(normal.into().as_ref(), start_idx as i32);
+            .setNormal(normal.as_ref(), start_idx as i32);
     }
 
     pub fn set_normals(&mut self, normals: &[Vec3], start_idx: usize) {
+        if let Some(staged) = &mut self.staged {
+            staged.normals = true;
+            let normals = normals.to_vec();
+            staged.push(move |output| output.set_normals(&normals, start_idx));
+            return;
+        }
         unsafe {
             self.output.as_mut().setNormals(
                 normals.as_ptr() as *const Vector,
@@ -59,19 +133,33 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn has_normals(&mut self) -> bool {
+        if let Some(staged) = &self.staged {
+            return staged.normals;
+        }
         self.output.as_mut().hasNormal()
     }
 
     pub fn set_color(&mut self, color: impl Into<Color>, start_idx: usize) {
+        let color = color.into();
+        if let Some(staged) = &mut self.staged {
+            staged.colors = true;
+            staged.push(move |output| output.set_color(color, start_idx));
+            return;
+        }
         unsafe {
-            self.output.as_mut().setColor(
-                &*(color.into().as_ref() as *const cxx::Color),
-                start_idx as i32,
-            );
+            self.output
+                .as_mut()
+                .setColor(&*(color.as_ref() as *const cxx::Color), start_idx as i32);
         }
     }
 
     pub fn set_colors(&mut self, colors: &[Color], start_idx: usize) {
+        if let Some(staged) = &mut self.staged {
+            staged.colors = true;
+            let colors = colors.to_vec();
+            staged.push(move |output| output.set_colors(&colors, start_idx));
+            return;
+        }
         unsafe {
             self.output.as_mut().setColors(
                 colors.as_ptr() as *const cxx::Color,
@@ -82,6 +170,9 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn has_color(&mut self) -> bool {
+        if let Some(staged) = &self.staged {
+            return staged.colors;
+        }
         self.output.as_mut().hasColor()
     }
 
@@ -91,9 +182,15 @@ impl<'execute> SopOutput<'execute> {
         num_layers: usize,
         start_idx: usize,
     ) {
+        let texture = texture.into();
+        if let Some(staged) = &mut self.staged {
+            staged.tex_coord_layers = staged.tex_coord_layers.max(num_layers);
+            staged.push(move |output| output.set_tex_coord(texture, num_layers, start_idx));
+            return;
+        }
         unsafe {
             self.output.as_mut().setTexCoord(
-                &*(texture.into().as_ref() as *const cxx::TexCoord),
+                &*(texture.as_ref() as *const cxx::TexCoord),
                 num_layers as i32,
                 start_idx as i32,
             );
@@ -101,6 +198,10 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn set_tex_coord2(&mut self, texture: &TexCoord, num_layers: usize, start_idx: usize) {
+        if self.staged.is_some() {
+            self.set_tex_coord(texture.clone(), num_layers, start_idx);
+            return;
+        }
         unsafe {
             self.output.as_mut().setTexCoord(
                 texture.as_ref() as *const cxx::TexCoord,
@@ -111,6 +212,12 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn set_tex_coords(&mut self, textures: &[TexCoord], num_layers: usize, start_idx: usize) {
+        if let Some(staged) = &mut self.staged {
+            staged.tex_coord_layers = staged.tex_coord_layers.max(num_layers);
+            let textures = textures.to_vec();
+            staged.push(move |output| output.set_tex_coords(&textures, num_layers, start_idx));
+            return;
+        }
         unsafe {
             let textures = textures
                 .iter()
@@ -133,14 +240,46 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn has_tex_coord(&mut self) -> bool {
+        if let Some(staged) = &self.staged {
+            return staged.tex_coord_layers > 0;
+        }
         self.output.as_mut().hasTexCoord()
     }
 
     pub fn num_tex_coord_layers(&mut self) -> usize {
+        if let Some(staged) = &self.staged {
+            return staged.tex_coord_layers;
+        }
         self.output.as_mut().getNumTexCoordLayers() as usize
     }
 
     pub fn set_custom_attribute(&mut self, attr: &CustomAttributeData, num_pts: usize) {
+        if let Some(staged) = &mut self.staged {
+            staged.custom_attributes = true;
+            // The attribute borrows its name and data, so copy them.
+            let name = unsafe { std::ffi::CStr::from_ptr(attr._base.name) }
+                .to_string_lossy()
+                .into_owned();
+            let size = attr._base.numComponents as usize;
+            let len = size * num_pts;
+            match attr.attr_type() {
+                AttributeType::Float => {
+                    let data = unsafe { std::slice::from_raw_parts(attr.floatData, len) }.to_vec();
+                    staged.push(move |output| {
+                        let attr = CustomAttributeData::new_float(&name, &data, size);
+                        output.set_custom_attribute(&attr, num_pts);
+                    });
+                }
+                AttributeType::Int => {
+                    let data = unsafe { std::slice::from_raw_parts(attr.intData, len) }.to_vec();
+                    staged.push(move |output| {
+                        let attr = CustomAttributeData::new_int(&name, &data, size);
+                        output.set_custom_attribute(&attr, num_pts);
+                    });
+                }
+            }
+            return;
+        }
         unsafe {
             let attr: *const CustomAttributeData = attr;
             self.output
@@ -150,16 +289,30 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn has_custom_attribute(&mut self) -> bool {
+        if let Some(staged) = &self.staged {
+            return staged.custom_attributes;
+        }
         self.output.as_mut().hasCustomAttibutes()
     }
 
     pub fn add_triangle(&mut self, x: u32, y: u32, z: u32) {
+        if let Some(staged) = &mut self.staged {
+            staged.num_primitives += 1;
+            staged.push(move |output| output.add_triangle(x, y, z));
+            return;
+        }
         self.output
             .as_mut()
             .addTriangle(x as i32, y as i32, z as i32);
     }
 
     pub fn add_triangles(&mut self, indices: &[u32]) {
+        if let Some(staged) = &mut self.staged {
+            staged.num_primitives += indices.len() / 3;
+            let indices = indices.to_vec();
+            staged.push(move |output| output.add_triangles(&indices));
+            return;
+        }
         unsafe {
             self.output
                 .as_mut()
@@ -168,12 +321,23 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn add_particle_system(&mut self, num_pts: usize, start_idx: usize) {
+        if let Some(staged) = &mut self.staged {
+            staged.num_primitives += 1;
+            staged.push(move |output| output.add_particle_system(num_pts, start_idx));
+            return;
+        }
         self.output
             .as_mut()
             .addParticleSystem(num_pts as i32, start_idx as i32);
     }
 
     pub fn add_line(&mut self, indices: &[u32]) {
+        if let Some(staged) = &mut self.staged {
+            staged.num_primitives += 1;
+            let indices = indices.to_vec();
+            staged.push(move |output| output.add_line(&indices));
+            return;
+        }
         unsafe {
             self.output
                 .as_mut()
@@ -182,6 +346,13 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn add_lines(&mut self, indices: &[u32], sizes: &[u32]) {
+        if let Some(staged) = &mut self.staged {
+            staged.num_primitives += sizes.len();
+            let indices = indices.to_vec();
+            let sizes = sizes.to_vec();
+            staged.push(move |output| output.add_lines(&indices, &sizes));
+            return;
+        }
         unsafe {
             self.output.as_mut().addLines(
                 indices.as_ptr() as *const i32,
@@ -192,14 +363,27 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn num_primitives(&mut self) -> usize {
+        if let Some(staged) = &self.staged {
+            return staged.num_primitives;
+        }
         self.output.as_mut().getNumPrimitives() as usize
     }
 
     pub fn set_bounding_box(&mut self, b: impl Into<BoundingBox>) {
-        self.output.as_mut().setBoundingBox(&b.into());
+        let b = b.into();
+        if let Some(staged) = &mut self.staged {
+            staged.push(move |output| output.set_bounding_box(b));
+            return;
+        }
+        self.output.as_mut().setBoundingBox(&b);
     }
 
     pub fn add_group(&mut self, type_: GroupType, name: &str) {
+        if let Some(staged) = &mut self.staged {
+            let name = name.to_owned();
+            staged.push(move |output| output.add_group(type_, &name));
+            return;
+        }
         let name = std::ffi::CString::new(name).unwrap();
         unsafe {
             self.output.as_mut().addGroup(&type_.into(), name.as_ptr());
@@ -207,6 +391,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn destroy_group(&mut self, type_: GroupType, name: &str) {
+        if let Some(staged) = &mut self.staged {
+            let name = name.to_owned();
+            staged.push(move |output| output.destroy_group(type_, &name));
+            return;
+        }
         let name = std::ffi::CString::new(name).unwrap();
         unsafe {
             self.output
@@ -216,6 +405,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn add_point_to_group(&mut self, point: usize, group: &str) {
+        if let Some(staged) = &mut self.staged {
+            let group = group.to_owned();
+            staged.push(move |output| output.add_point_to_group(point, &group));
+            return;
+        }
         let group = std::ffi::CString::new(group).unwrap();
         unsafe {
             self.output
@@ -225,6 +419,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn add_prim_to_group(&mut self, prim: usize, group: &str) {
+        if let Some(staged) = &mut self.staged {
+            let group = group.to_owned();
+            staged.push(move |output| output.add_prim_to_group(prim, &group));
+            return;
+        }
         let group = std::ffi::CString::new(group).unwrap();
         unsafe {
             self.output
@@ -234,6 +433,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn add_to_group(&mut self, idx: usize, type_: GroupType, group: &str) {
+        if let Some(staged) = &mut self.staged {
+            let group = group.to_owned();
+            staged.push(move |output| output.add_to_group(idx, type_, &group));
+            return;
+        }
         let group = std::ffi::CString::new(group).unwrap();
         unsafe {
             self.output.as_mut().addToGroup(
@@ -245,6 +449,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn discard_from_point_group(&mut self, idx: usize, name: &str) {
+        if let Some(staged) = &mut self.staged {
+            let name = name.to_owned();
+            staged.push(move |output| output.discard_from_point_group(idx, &name));
+            return;
+        }
         let name = std::ffi::CString::new(name).unwrap();
         unsafe {
             self.output
@@ -254,6 +463,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn discard_from_prim_group(&mut self, idx: usize, name: &str) {
+        if let Some(staged) = &mut self.staged {
+            let name = name.to_owned();
+            staged.push(move |output| output.discard_from_prim_group(idx, &name));
+            return;
+        }
         let name = std::ffi::CString::new(name).unwrap();
         unsafe {
             self.output
@@ -263,6 +477,11 @@ impl<'execute> SopOutput<'execute> {
     }
 
     pub fn discard_from_group(&mut self, idx: usize, type_: GroupType, name: &str) {
+        if let Some(staged) = &mut self.staged {
+            let name = name.to_owned();
+            staged.push(move |output| output.discard_from_group(idx, type_, &name));
+            return;
+        }
         let name = std::ffi::CString::new(name).unwrap();
         unsafe {
             self.output.as_mut().discardFromGroup(
@@ -359,6 +578,11 @@ impl<'execute, State> SopVboOutput<'execute, State> {
 }
 
 impl<'execute> SopVboOutput<'execute, Unalloc> {
+    /// Output empty geometry.
+    pub fn clear(self) {
+        self.alloc_none(0, 0, BufferMode::Static).update_complete();
+    }
+
     pub fn add_custom_attribute(&mut self, attr: CustomAttributeInfo) {
         let name = std::ffi::CString::new(attr.name).unwrap();
         let attr = cxx::SOP_CustomAttribInfo {
@@ -661,6 +885,38 @@ pub trait Sop: Op {
     fn execute_vbo(&mut self, _output: SopVboOutput<Unalloc>, _inputs: &OperatorInputs<SopInput>) {
         // Do nothing by default.
     }
+
+    /// Fallible version of [`Sop::execute`], which is what the framework
+    /// calls. An error is reported on the node and the output is handled
+    /// according to [`Sop::error_output`].
+    fn try_execute(
+        &mut self,
+        output: &mut SopOutput,
+        inputs: &OperatorInputs<SopInput>,
+    ) -> Result<(), OpError> {
+        self.execute(output, inputs);
+        Ok(())
+    }
+
+    /// Fallible version of [`Sop::execute_vbo`], which is what the framework
+    /// calls. An error is reported on the node and the output is handled
+    /// according to [`Sop::error_output`].
+    fn try_execute_vbo(
+        &mut self,
+        output: SopVboOutput<Unalloc>,
+        inputs: &OperatorInputs<SopInput>,
+    ) -> Result<(), OpError> {
+        self.execute_vbo(output, inputs);
+        Ok(())
+    }
+
+    /// What to do with the output when [`Sop::try_execute`] or
+    /// [`Sop::try_execute_vbo`] fails. [`ErrorOutput::Empty`] outputs no
+    /// geometry, which for [`Sop::try_execute`] means holding the geometry
+    /// back until execute succeeds.
+    fn error_output(&self) -> ErrorOutput {
+        ErrorOutput::Unchanged
+    }
 }
 
 #[macro_export]
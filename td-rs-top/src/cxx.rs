@@ -5,7 +5,9 @@ use autocxx::prelude::*;
 use autocxx::subclass::*;
 use std::ffi::CString;
 use std::pin::Pin;
-use td_rs_base::{param::ParameterManager, NodeInfo, OperatorInputs};
+use td_rs_base::{
+    param::ParameterManager, ErrorOutput, NodeInfo, Op, OpMessages, OperatorInputs, PanicGuard,
+};
 
 use crate::{TopContext, TopOutput};
 // use crate::mode::cpu::{TopCpuInput, TopCpuOutput};
@@ -60,6 +62,8 @@ extern "C" {
 #[subclass(superclass("RustTopPlugin"))]
 pub struct RustTopPluginImpl {
     inner: Box<dyn Top>,
+    messages: OpMessages,
+    panic: PanicGuard,
 }
 
 // SAFETY: This can only be used with pointers returned from getNodeInstance() and
@@ -86,17 +90,32 @@ extern "C" fn top_new(
     info: &'static OP_NodeInfo,
     context: Pin<&'static mut TOP_Context>,
 ) -> *mut RustTopPluginImplCpp {
-    unsafe {
-        let info = NodeInfo::new(info);
-        let context = TopContext::new(context);
-        RustTopPluginImpl::new_cpp_owned(RustTopPluginImpl {
-            inner: top_new_impl(info, context),
-            cpp_peer: CppSubclassCppPeerHolder::Empty,
-        })
-        .into_raw()
-    }
+    let info = NodeInfo::new(info);
+    let context = TopContext::new(context);
+    let mut messages = OpMessages::new();
+    let mut panic = PanicGuard::new();
+    let inner = panic.call(&mut messages, || unsafe { top_new_impl(info, context) });
+    let inner = inner.unwrap_or_else(|| {
+        panic.set_fatal();
+        Box::new(FailedTop)
+    });
+    RustTopPluginImpl::new_cpp_owned(RustTopPluginImpl {
+        inner,
+        messages,
+        panic,
+        cpp_peer: CppSubclassCppPeerHolder::Empty,
+    })
+    .into_raw()
 }
 
+/// Stands in for a plugin whose constructor panicked, so the node is still
+/// created and shows the panic as its error.
+struct FailedTop;
+
+impl Op for FailedTop {}
+
+impl Top for FailedTop {}
+
 impl RustTopPlugin_methods for RustTopPluginImpl {
     fn inner(&self) -> *mut c_void {
         self.inner.as_ref() as *const dyn Top as *mut c_void
@@ -109,58 +128,108 @@ impl RustTopPlugin_methods for RustTopPluginImpl {
     fn getGeneralInfo(&mut self, mut info: Pin<&mut TOP_GeneralInfo>, inputs: &OP_Inputs) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getGeneralInfo").entered() };
-
+        if !self.panic.is_poisoned() {
+            self.messages.clear();
+        }
         let input = OperatorInputs::new(inputs);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return;
+        }
+        let gen_info = self
+            .panic
+            .call(&mut self.messages, || self.inner.general_info(&input));
+        if let Some(gen_info) = gen_info {
+            info.cookEveryFrame = gen_info.cook_every_frame;
+            info.cookEveryFrameIfAsked = gen_info.cook_every_frame_if_asked;
+            info.inputSizeIndex = gen_info.input_size_index;
         }
-        let gen_info = self.inner.general_info(&input);
-        info.cookEveryFrame = gen_info.cook_every_frame;
-        info.cookEveryFrameIfAsked = gen_info.cook_every_frame_if_asked;
-        info.inputSizeIndex = gen_info.input_size_index;
     }
 
-    fn getNumInfoCHOPChans(&mut self) -> i32 {
+    fn execute(&mut self, mut output: Pin<&mut TOP_Output>, inputs: &OP_Inputs) {
         #[cfg(feature = "tracing")]
-        let _span = { tracing_base::trace_span!("getNumInfoCHOPChans").entered() };
-        if let Some(info_chop) = self.inner.info_chop() {
-            info_chop.size() as i32
-        } else {
-            0
+        let _span = { tracing_base::trace_span!("execute").entered() };
+        let input = OperatorInputs::new(inputs);
+        self.panic.update_params(
+            &mut self.messages,
+            || self.inner.params_mut(),
+            &input.params(),
+        );
+        if self.panic.is_poisoned() {
+            return;
+        }
+        let res = self.panic.call(&mut self.messages, || {
+            let mut staged = Vec::new();
+            let top_output = match self.inner.error_output() {
+                ErrorOutput::Unchanged => TopOutput::new(output.as_mut()),
+                ErrorOutput::Empty => TopOutput::staged(output.as_mut(), &mut staged),
+            };
+            let res = self.inner.try_execute(top_output, &input);
+            if res.is_ok() {
+                TopOutput::new(output).commit(staged);
+            }
+            res
+        });
+        if let Some(Err(err)) = res {
+            self.messages.report(err);
         }
+        self.panic
+            .end_cook(&mut self.messages, || self.inner.params_mut());
     }
 
-    // TOP_OutputFormatSpecs &output_specs, const OP_Inputs &inputs, TOP_Context &context
-    fn execute(&mut self, output: Pin<&mut TOP_Output>, inputs: &OP_Inputs) {
+    fn getNumInfoCHOPChans(&mut self) -> i32 {
         #[cfg(feature = "tracing")]
-        let _span = { tracing_base::trace_span!("execute").entered() };
-        let input = OperatorInputs::new(inputs);
-        let output = TopOutput::new(output);
-        if let Some(params) = self.inner.params_mut() {
-            params.update(&input.params());
+        let _span = { tracing_base::trace_span!("getNumInfoCHOPChans").entered() };
+        if self.panic.is_poisoned() {
+            return 0;
         }
-        self.inner.execute(output, &input);
+        self.panic
+            .call(&mut self.messages, || {
+                if let Some(info_chop) = self.inner.info_chop() {
+                    info_chop.size() as i32
+                } else {
+                    0
+                }
+            })
+            .unwrap_or(0)
     }
 
     fn getInfoCHOPChan(&mut self, index: i32, name: Pin<&mut OP_String>, mut value: Pin<&mut f32>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoCHOPChan").entered() };
-        if let Some(info_chop) = self.inner.info_chop() {
-            let (info_name, info_value) = info_chop.channel(index as usize);
-            unsafe {
-                let new_string = CString::new(info_name.as_str()).unwrap();
-                let new_string_ptr = new_string.as_ptr();
-                name.setString(new_string_ptr);
-            }
-            value.set(info_value);
+        if self.panic.is_poisoned() {
+            return;
         }
+        self.panic.call(&mut self.messages, || {
+            if let Some(info_chop) = self.inner.info_chop() {
+                let (info_name, info_value) = info_chop.channel(index as usize);
+                unsafe {
+                    let new_string = CString::new(info_name.as_str()).unwrap();
+                    let new_string_ptr = new_string.as_ptr();
+                    name.setString(new_string_ptr);
+                }
+                value.set(info_value);
+            }
+        });
     }
 
     fn getInfoDATSize(&mut self, mut info: Pin<&mut OP_InfoDATSize>) -> bool {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoDATSize").entered() };
-        if let Some(info_dat) = self.inner.info_dat() {
-            let (rows, cols) = info_dat.size();
+        if self.panic.is_poisoned() {
+            return false;
+        }
+        let size = self
+            .panic
+            .call(&mut self.messages, || {
+                self.inner.info_dat().map(|info_dat| info_dat.size())
+            })
+            .flatten();
+        if let Some((rows, cols)) = size {
             info.rows = rows as i32;
             info.cols = cols as i32;
             true
@@ -172,22 +241,27 @@ impl RustTopPlugin_methods for RustTopPluginImpl {
     fn getInfoDATEntry(&mut self, index: i32, entryIndex: i32, entry: Pin<&mut OP_String>) {
         #[cfg(feature = "tracing")]
         let _span = { tracing_base::trace_span!("getInfoDATEntry").entered() };
-        if let Some(info_dat) = self.inner.info_dat() {
-            let entry_str = info_dat.entry(index as usize, entryIndex as usize);
-            if entry_str.is_empty() {
-                return;
-            }
-            unsafe {
-                let new_string = CString::new(entry_str.as_str()).unwrap();
-                let new_string_ptr = new_string.as_ptr();
-                entry.setString(new_string_ptr);
-            }
+        if self.panic.is_poisoned() {
+            return;
         }
+        self.panic.call(&mut self.messages, || {
+            if let Some(info_dat) = self.inner.info_dat() {
+                let entry_str = info_dat.entry(index as usize, entryIndex as usize);
+                if entry_str.is_empty() {
+                    return;
+                }
+                unsafe {
+                    let new_string = CString::new(entry_str.as_str()).unwrap();
+                    let new_string_ptr = new_string.as_ptr();
+                    entry.setString(new_string_ptr);
+                }
+            }
+        });
     }
 
     fn getWarningString(&mut self, warning: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.warning()).unwrap();
+            let new_string = CString::new(self.messages.warning()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             warning.setString(new_string_ptr);
         }
@@ -195,7 +269,7 @@ impl RustTopPlugin_methods for RustTopPluginImpl {
 
     fn getErrorString(&mut self, error: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.error()).unwrap();
+            let new_string = CString::new(self.messages.error()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             error.setString(new_string_ptr);
         }
@@ -203,7 +277,7 @@ impl RustTopPlugin_methods for RustTopPluginImpl {
 
     fn getInfoPopupString(&mut self, info: Pin<&mut OP_String>) {
         unsafe {
-            let new_string = CString::new(self.inner.info()).unwrap();
+            let new_string = CString::new(self.messages.info()).unwrap_or_default();
             let new_string_ptr = new_string.as_ptr();
             info.setString(new_string_ptr);
         }
@@ -211,22 +285,28 @@ impl RustTopPlugin_methods for RustTopPluginImpl {
 
     fn setupParameters(&mut self, manager: Pin<&mut OP_ParameterManager>) {
         #[cfg(feature = "tracing")]
-        {
-            let span = tracing_base::trace_span!("setupParameters").entered();
-        }
-        let params = self.inner.params_mut();
-        if let Some(params) = params {
-            let mut manager = ParameterManager::new(manager);
-            params.register(&mut manager);
-        }
+        let _span = { tracing_base::trace_span!("setupParameters").entered() };
+        self.panic.call(&mut self.messages, || {
+            let params = self.inner.params_mut();
+            if let Some(params) = params {
+                let mut manager = ParameterManager::new(manager);
+                params.register(&mut manager);
+            }
+        });
     }
 
     unsafe fn pulsePressed(&mut self, name: *const std::ffi::c_char) {
         #[cfg(feature = "tracing")]
-        {
-            let span = tracing_base::trace_span!("pulsePressed").entered();
+        let _span = { tracing_base::trace_span!("pulsePressed").entered() };
+        // A pulse counts as a parameter change, so give a poisoned
+        // instance another chance to cook.
+        if self.panic.is_poisoned() {
+            self.panic.reset(&mut self.messages);
         }
-        self.inner
-            .pulse_pressed(std::ffi::CStr::from_ptr(name).to_str().unwrap());
+        self.panic.call(&mut self.messages, || {
+            let name = std::ffi::CStr::from_ptr(name).to_str().unwrap();
+            self.inner.dispatch_pulse(name);
+            self.inner.pulse_pressed(name);
+        });
     }
 }
@@ -8,11 +8,41 @@ pub use td_rs_base::*;
 
 pub struct TopOutput<'execute> {
     output: Pin<&'execute mut cxx::TOP_Output>,
+    staged: Option<&'execute mut Vec<StagedUpload>>,
+}
+
+/// An upload held back from the output until execute succeeds, so that
+/// [`ErrorOutput::Empty`] can discard it.
+pub(crate) struct StagedUpload {
+    buffer: UniquePtr<cxx::TD_OP_SmartRef_TD_TOP_Buffer_AutocxxConcrete>,
+    info: cxx::TOP_UploadInfo,
 }
 
 impl<'execute> TopOutput<'execute> {
     pub fn new(output: Pin<&'execute mut cxx::TOP_Output>) -> TopOutput<'execute> {
-        Self { output }
+        Self {
+            output,
+            staged: None,
+        }
+    }
+
+    /// Create a `TopOutput` which collects its uploads in `staged` rather
+    /// than uploading them, to be passed to [`TopOutput::commit`].
+    pub(crate) fn staged(
+        output: Pin<&'execute mut cxx::TOP_Output>,
+        staged: &'execute mut Vec<StagedUpload>,
+    ) -> TopOutput<'execute> {
+        Self {
+            output,
+            staged: Some(staged),
+        }
+    }
+
+    /// Upload the buffers held back by a staged output.
+    pub(crate) fn commit(mut self, staged: Vec<StagedUpload>) {
+        for upload in staged {
+            self.upload(upload.buffer, &upload.info);
+        }
     }
 
     pub fn upload_buffer(&mut self, buffer: &mut TopBuffer, info: &UploadInfo) {
@@ -44,10 +74,21 @@ impl<'execute> TopOutput<'execute> {
 
         // uploadBuffer takes ownership of the buffer
         let buf = std::mem::replace(&mut buffer.buffer, UniquePtr::null());
+        match &mut self.staged {
+            Some(staged) => staged.push(StagedUpload { buffer: buf, info }),
+            None => self.upload(buf, &info),
+        }
+    }
+
+    fn upload(
+        &mut self,
+        buf: UniquePtr<cxx::TD_OP_SmartRef_TD_TOP_Buffer_AutocxxConcrete>,
+        info: &cxx::TOP_UploadInfo,
+    ) {
         unsafe {
             self.output
                 .as_mut()
-                .uploadBuffer(buf.into_raw(), &info, std::ptr::null_mut())
+                .uploadBuffer(buf.into_raw(), info, std::ptr::null_mut())
         };
     }
 }
@@ -159,6 +200,25 @@ pub trait Top: Op {
     }
 
     fn execute(&mut self, _output: TopOutput, _input: &OperatorInputs<TopInput>) {}
+
+    /// Fallible version of [`Top::execute`], which is what the framework
+    /// calls. An error is reported on the node and the output is handled
+    /// according to [`Top::error_output`].
+    fn try_execute(
+        &mut self,
+        output: TopOutput,
+        input: &OperatorInputs<TopInput>,
+    ) -> Result<(), OpError> {
+        self.execute(output, input);
+        Ok(())
+    }
+
+    /// What to do with the output when [`Top::try_execute`] fails.
+    /// [`ErrorOutput::Empty`] holds uploads back until execute succeeds, so
+    /// nothing is uploaded by a failed cook.
+    fn error_output(&self) -> ErrorOutput {
+        ErrorOutput::Unchanged
+    }
 }
 
 #[macro_export]